@@ -0,0 +1,20 @@
+use std::env;
+use std::path::Path;
+
+// Generates the C header into OUT_DIR, where the tests compile against it. The copy in
+// include/ is only rewritten when CUBE_SOLVER_UPDATE_HEADER is set, the tests check it's
+// up to date
+fn main() {
+    let dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let config = cbindgen::Config::from_file(Path::new(&dir).join("cbindgen.toml")).unwrap();
+    let bindings = cbindgen::generate_with_config(&dir, config).expect("couldn't generate the C header");
+    bindings.write_to_file(Path::new(&out_dir).join("include/cube_solver.h"));
+    if env::var_os("CUBE_SOLVER_UPDATE_HEADER").is_some() {
+        bindings.write_to_file(Path::new(&dir).join("include/cube_solver.h"));
+    }
+
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=CUBE_SOLVER_UPDATE_HEADER");
+}
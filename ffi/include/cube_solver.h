@@ -0,0 +1,144 @@
+#ifndef CUBE_SOLVER_H
+#define CUBE_SOLVER_H
+
+/* Generated by cbindgen from src/lib.rs, don't edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Result of every call, mirrors the crate's errors
+ */
+typedef enum SolverStatus {
+  SOLVER_STATUS_OK = 0,
+  /**
+   * No solution within `max_len` moves
+   */
+  SOLVER_STATUS_NO_SOLUTION = 1,
+  /**
+   * A pointer was NULL or a string wasn't UTF-8
+   */
+  SOLVER_STATUS_INVALID_ARGUMENT = 2,
+  /**
+   * `solver_init` hasn't been called
+   */
+  SOLVER_STATUS_NOT_INITIALISED = 3,
+  /**
+   * The solution doesn't fit in `out_buf`
+   */
+  SOLVER_STATUS_BUFFER_TOO_SMALL = 4,
+  /**
+   * Something panicked, this is a bug
+   */
+  SOLVER_STATUS_INTERNAL = 5,
+  /**
+   * Reading or writing a table failed
+   */
+  SOLVER_STATUS_IO = 10,
+  /**
+   * A table file is the wrong size
+   */
+  SOLVER_STATUS_CORRUPT_TABLE = 11,
+  /**
+   * Facelet string isn't 54 letters
+   */
+  SOLVER_STATUS_FACELET_LENGTH = 20,
+  /**
+   * Letter other than U, R, F, D, L, B
+   */
+  SOLVER_STATUS_FACELET_LETTER = 21,
+  /**
+   * A colour isn't on exactly 9 facelets
+   */
+  SOLVER_STATUS_COLOR_COUNT = 22,
+  /**
+   * Centres aren't in URFDLB order
+   */
+  SOLVER_STATUS_CENTERS = 23,
+  /**
+   * Corner with colours no corner has
+   */
+  SOLVER_STATUS_INVALID_CORNER = 24,
+  /**
+   * Edge with colours no edge has
+   */
+  SOLVER_STATUS_INVALID_EDGE = 25,
+  /**
+   * Some corner is missing or there twice
+   */
+  SOLVER_STATUS_CORNER_PERMUTATION = 30,
+  /**
+   * Some edge is missing or there twice
+   */
+  SOLVER_STATUS_EDGE_PERMUTATION = 31,
+  /**
+   * A corner is twisted
+   */
+  SOLVER_STATUS_TWIST = 32,
+  /**
+   * An edge is flipped
+   */
+  SOLVER_STATUS_FLIP = 33,
+  /**
+   * Two pieces are swapped
+   */
+  SOLVER_STATUS_PARITY = 34,
+  /**
+   * Can't be solved with the solver's moves
+   */
+  SOLVER_STATUS_UNREACHABLE = 40,
+  /**
+   * Ran out of time before finding a solution
+   */
+  SOLVER_STATUS_TIMEOUT = 41,
+  /**
+   * Search was cancelled
+   */
+  SOLVER_STATUS_CANCELLED = 42,
+} SolverStatus;
+
+/**
+ * Load the tables, generating any that are missing. `tables_dir` is relative to `tables/`
+ * unless it's absolute, NULL means `tables/` itself. Replaces any solver already loaded
+ *
+ * # Safety
+ * `tables_dir` must be NULL or a NUL terminated string
+ */
+enum SolverStatus solver_init(const char *tables_dir);
+
+/**
+ * Solve a cube in at most `max_len` moves, giving up after `timeout_ms` (0 for no limit).
+ * The solution is written to `out_buf` as a NUL terminated string like `R U2 F'`
+ *
+ * # Safety
+ * `facelets` must be a NUL terminated string and `out_buf` must have room for `out_len` bytes
+ */
+enum SolverStatus solver_solve(const char *facelets,
+                               uint32_t max_len,
+                               uint32_t timeout_ms,
+                               char *out_buf,
+                               size_t out_len);
+
+/**
+ * Check a facelet string is a cube that can be solved, without needing `solver_init`
+ *
+ * # Safety
+ * `facelets` must be a NUL terminated string
+ */
+enum SolverStatus solver_verify(const char *facelets);
+
+/**
+ * Unload the tables, `solver_init` has to be called again before solving
+ */
+void solver_free(void);
+
+/**
+ * Short description of a status, never NULL. Anything that isn't a `SolverStatus` is
+ * "unknown status"
+ */
+const char *solver_status_message(int status);
+
+#endif  /* CUBE_SOLVER_H */
@@ -0,0 +1,250 @@
+//! C interface to the solver. One solver is loaded at a time by `solver_init` and shared by
+//! every thread until `solver_free`. Facelet strings are the 54 letter `UUUUUUUUURRR...` form
+
+use std::ffi::{c_char, c_int, CStr};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use cube_solver::{
+    cubie::{CubieCube, CubieError},
+    error::Error,
+    face::{FaceCube, FaceletError},
+    search::{Limits, Solver},
+};
+
+/// Result of every call, mirrors the crate's errors
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverStatus {
+    Ok = 0,
+    /// No solution within `max_len` moves
+    NoSolution = 1,
+    /// A pointer was NULL or a string wasn't UTF-8
+    InvalidArgument = 2,
+    /// `solver_init` hasn't been called
+    NotInitialised = 3,
+    /// The solution doesn't fit in `out_buf`
+    BufferTooSmall = 4,
+    /// Something panicked, this is a bug
+    Internal = 5,
+
+    /// Reading or writing a table failed
+    Io = 10,
+    /// A table file is the wrong size
+    CorruptTable = 11,
+
+    /// Facelet string isn't 54 letters
+    FaceletLength = 20,
+    /// Letter other than U, R, F, D, L, B
+    FaceletLetter = 21,
+    /// A colour isn't on exactly 9 facelets
+    ColorCount = 22,
+    /// Centres aren't in URFDLB order
+    Centers = 23,
+    /// Corner with colours no corner has
+    InvalidCorner = 24,
+    /// Edge with colours no edge has
+    InvalidEdge = 25,
+
+    /// Some corner is missing or there twice
+    CornerPermutation = 30,
+    /// Some edge is missing or there twice
+    EdgePermutation = 31,
+    /// A corner is twisted
+    Twist = 32,
+    /// An edge is flipped
+    Flip = 33,
+    /// Two pieces are swapped
+    Parity = 34,
+
+    /// Can't be solved with the solver's moves
+    Unreachable = 40,
+    /// Ran out of time before finding a solution
+    Timeout = 41,
+    /// Search was cancelled
+    Cancelled = 42,
+}
+
+impl SolverStatus {
+    const ALL: [SolverStatus; 22] = [
+        SolverStatus::Ok, SolverStatus::NoSolution, SolverStatus::InvalidArgument, SolverStatus::NotInitialised,
+        SolverStatus::BufferTooSmall, SolverStatus::Internal, SolverStatus::Io, SolverStatus::CorruptTable,
+        SolverStatus::FaceletLength, SolverStatus::FaceletLetter, SolverStatus::ColorCount, SolverStatus::Centers,
+        SolverStatus::InvalidCorner, SolverStatus::InvalidEdge, SolverStatus::CornerPermutation,
+        SolverStatus::EdgePermutation, SolverStatus::Twist, SolverStatus::Flip, SolverStatus::Parity,
+        SolverStatus::Unreachable, SolverStatus::Timeout, SolverStatus::Cancelled,
+    ];
+
+    /// The status with value `status`, if there is one. C can pass any int where it takes
+    /// the enum, so it's checked rather than transmuted
+    fn from_int(status: c_int) -> Option<Self> {
+        Self::ALL.into_iter().find(|&s| s as c_int == status)
+    }
+}
+
+impl From<Error> for SolverStatus {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Io(_) => SolverStatus::Io,
+            Error::CorruptTable(_) | Error::InvalidTable(_) => SolverStatus::CorruptTable,
+            Error::InvalidFacelets(f) => match f {
+                FaceletError::InvalidLetter(_) => SolverStatus::FaceletLetter,
+                FaceletError::WrongLength(_) => SolverStatus::FaceletLength,
+                FaceletError::ColorCount(_) => SolverStatus::ColorCount,
+                FaceletError::Centers => SolverStatus::Centers,
+                FaceletError::InvalidCorner(_) => SolverStatus::InvalidCorner,
+                FaceletError::InvalidEdge(_) => SolverStatus::InvalidEdge,
+            },
+            Error::InvalidCube(c) => match c {
+                CubieError::CornerPermutation => SolverStatus::CornerPermutation,
+                CubieError::EdgePermutation => SolverStatus::EdgePermutation,
+                CubieError::Twist => SolverStatus::Twist,
+                CubieError::Flip => SolverStatus::Flip,
+                CubieError::Parity => SolverStatus::Parity,
+            },
+            // Not something the C API can produce
+            Error::InvalidColors(_) | Error::InvalidNotation(_) | Error::OverBudget { .. } | Error::InvalidMetric(_)
+            | Error::NoScramble(_) => SolverStatus::InvalidArgument,
+            Error::Unreachable => SolverStatus::Unreachable,
+            Error::Timeout => SolverStatus::Timeout,
+            Error::Cancelled => SolverStatus::Cancelled,
+        }
+    }
+}
+
+static SOLVER: RwLock<Option<Arc<Solver>>> = RwLock::new(None);
+
+/// Runs `f`, turning errors and panics into a status. Nothing `f` touches is used after a
+/// panic except the solver, which is never left half changed
+fn guard(f: impl FnOnce() -> Result<(), SolverStatus>) -> SolverStatus {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => SolverStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => SolverStatus::Internal,
+    }
+}
+
+/// # Safety
+/// `s` must be NULL or a NUL terminated string
+unsafe fn to_str<'a>(s: *const c_char) -> Result<&'a str, SolverStatus> {
+    if s.is_null() {
+        return Err(SolverStatus::InvalidArgument);
+    }
+    CStr::from_ptr(s).to_str().map_err(|_| SolverStatus::InvalidArgument)
+}
+
+fn parse(facelets: &str) -> Result<CubieCube, SolverStatus> {
+    let cube = facelets.parse::<FaceCube>().map_err(Error::from)?.to_cubie()?;
+    Ok(cube)
+}
+
+/// Load the tables, generating any that are missing. `tables_dir` is relative to `tables/`
+/// unless it's absolute, NULL means `tables/` itself. Replaces any solver already loaded
+///
+/// # Safety
+/// `tables_dir` must be NULL or a NUL terminated string
+#[no_mangle]
+pub unsafe extern "C" fn solver_init(tables_dir: *const c_char) -> SolverStatus {
+    let dir = if tables_dir.is_null() {
+        None
+    } else {
+        match to_str(tables_dir) {
+            Ok(d) => Some(d),
+            Err(status) => return status,
+        }
+    };
+
+    guard(|| {
+        let solver = Solver::new(dir.map(Path::new))?;
+        *SOLVER.write().map_err(|_| SolverStatus::Internal)? = Some(Arc::new(solver));
+        Ok(())
+    })
+}
+
+/// Solve a cube in at most `max_len` moves, giving up after `timeout_ms` (0 for no limit).
+/// The solution is written to `out_buf` as a NUL terminated string like `R U2 F'`
+///
+/// # Safety
+/// `facelets` must be a NUL terminated string and `out_buf` must have room for `out_len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn solver_solve(facelets: *const c_char, max_len: u32, timeout_ms: u32, out_buf: *mut c_char, out_len: usize) -> SolverStatus {
+    let facelets = match to_str(facelets) {
+        Ok(f) => f,
+        Err(status) => return status,
+    };
+    if out_buf.is_null() || out_len == 0 {
+        return SolverStatus::InvalidArgument;
+    }
+    let out = std::slice::from_raw_parts_mut(out_buf as *mut u8, out_len);
+
+    guard(move || {
+        let solver = SOLVER.read().map_err(|_| SolverStatus::Internal)?.clone().ok_or(SolverStatus::NotInitialised)?;
+        let cube = parse(facelets)?;
+        let timeout = (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms as u64));
+        let report = solver.solve_with(&cube, max_len as usize, &Limits { timeout, cancel: None })?;
+        let solution = report.solution.ok_or(SolverStatus::NoSolution)?.to_string();
+
+        if solution.len() >= out.len() {
+            return Err(SolverStatus::BufferTooSmall);
+        }
+        out[..solution.len()].copy_from_slice(solution.as_bytes());
+        out[solution.len()] = 0;
+        Ok(())
+    })
+}
+
+/// Check a facelet string is a cube that can be solved, without needing `solver_init`
+///
+/// # Safety
+/// `facelets` must be a NUL terminated string
+#[no_mangle]
+pub unsafe extern "C" fn solver_verify(facelets: *const c_char) -> SolverStatus {
+    match to_str(facelets) {
+        Ok(f) => guard(|| parse(f).map(|_| ())),
+        Err(status) => status,
+    }
+}
+
+/// Unload the tables, `solver_init` has to be called again before solving
+#[no_mangle]
+pub extern "C" fn solver_free() {
+    if let Ok(mut solver) = SOLVER.write() {
+        *solver = None;
+    }
+}
+
+/// Short description of a status, never NULL. Anything that isn't a `SolverStatus` is
+/// "unknown status"
+#[no_mangle]
+pub extern "C" fn solver_status_message(status: c_int) -> *const c_char {
+    let Some(status) = SolverStatus::from_int(status) else {
+        return c"unknown status".as_ptr();
+    };
+    let message: &'static CStr = match status {
+        SolverStatus::Ok => c"ok",
+        SolverStatus::NoSolution => c"no solution within the maximum length",
+        SolverStatus::InvalidArgument => c"invalid argument",
+        SolverStatus::NotInitialised => c"solver_init hasn't been called",
+        SolverStatus::BufferTooSmall => c"output buffer too small",
+        SolverStatus::Internal => c"internal error",
+        SolverStatus::Io => c"table I/O failed",
+        SolverStatus::CorruptTable => c"table is corrupt",
+        SolverStatus::FaceletLength => c"facelet string isn't 54 letters",
+        SolverStatus::FaceletLetter => c"invalid facelet letter",
+        SolverStatus::ColorCount => c"a colour isn't on 9 facelets",
+        SolverStatus::Centers => c"centres aren't in URFDLB order",
+        SolverStatus::InvalidCorner => c"a corner doesn't exist",
+        SolverStatus::InvalidEdge => c"an edge doesn't exist",
+        SolverStatus::CornerPermutation => c"not every corner is there exactly once",
+        SolverStatus::EdgePermutation => c"not every edge is there exactly once",
+        SolverStatus::Twist => c"a corner is twisted",
+        SolverStatus::Flip => c"an edge is flipped",
+        SolverStatus::Parity => c"two pieces are swapped",
+        SolverStatus::Unreachable => c"cube is unreachable with the solver's moves",
+        SolverStatus::Timeout => c"no solution found in time",
+        SolverStatus::Cancelled => c"search cancelled",
+    };
+    message.as_ptr()
+}
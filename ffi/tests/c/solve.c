@@ -0,0 +1,53 @@
+/* Exercises the C API, run by tests/c_api.rs
+ * usage: solve TABLES_DIR SCRAMBLED_FACELETS TWISTED_FACELETS
+ * Prints the solution of the scrambled cube and exits 0 if every check passed */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "cube_solver.h"
+
+#define SOLVED "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB"
+
+static int failures = 0;
+
+static void expect(const char *what, SolverStatus got, SolverStatus want) {
+    if (got != want) {
+        fprintf(stderr, "%s: got %d (%s), wanted %d\n", what, got, solver_status_message(got), want);
+        failures++;
+    }
+}
+
+int main(int argc, char **argv) {
+    char buf[128];
+    char tiny[4];
+
+    if (argc != 4) {
+        fprintf(stderr, "usage: solve TABLES_DIR SCRAMBLED TWISTED\n");
+        return 2;
+    }
+
+    expect("solve before init", solver_solve(argv[2], 20, 0, buf, sizeof buf), SOLVER_STATUS_NOT_INITIALISED);
+
+    expect("verify solved", solver_verify(SOLVED), SOLVER_STATUS_OK);
+    expect("verify short", solver_verify("UUU"), SOLVER_STATUS_FACELET_LENGTH);
+    expect("verify twisted", solver_verify(argv[3]), SOLVER_STATUS_TWIST);
+    expect("verify null", solver_verify(NULL), SOLVER_STATUS_INVALID_ARGUMENT);
+
+    if (strcmp(solver_status_message(SOLVER_STATUS_TWIST), "a corner is twisted") != 0
+        || strcmp(solver_status_message(99), "unknown status") != 0) {
+        fprintf(stderr, "status messages are wrong\n");
+        failures++;
+    }
+
+    expect("init", solver_init(argv[1]), SOLVER_STATUS_OK);
+    expect("solve twisted", solver_solve(argv[3], 20, 0, buf, sizeof buf), SOLVER_STATUS_TWIST);
+    expect("tiny buffer", solver_solve(argv[2], 20, 0, tiny, sizeof tiny), SOLVER_STATUS_BUFFER_TOO_SMALL);
+    expect("solve", solver_solve(argv[2], 20, 10000, buf, sizeof buf), SOLVER_STATUS_OK);
+    printf("%s\n", buf);
+
+    solver_free();
+    expect("solve after free", solver_solve(argv[2], 20, 0, buf, sizeof buf), SOLVER_STATUS_NOT_INITIALISED);
+
+    return failures == 0 ? 0 : 1;
+}
@@ -0,0 +1,68 @@
+// Compiles tests/c/solve.c against the generated header and the cdylib, then runs it
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use cube_solver::{common::Move::*, cubie::CubieCube, face::FaceCube, notation::parse_face_moves};
+
+/// Builds the cdylib with the same profile as the test, `cargo test` only builds the rlib.
+/// Returns the directory it's in, e.g. target/debug
+fn build_lib() -> PathBuf {
+    // The test is in target/<profile>/deps
+    let exe = env::current_exe().unwrap();
+    let lib_dir = exe.parent().unwrap().parent().unwrap().to_path_buf();
+    let profile = lib_dir.file_name().unwrap().to_str().unwrap();
+    let profile = if profile == "debug" { "dev" } else { profile };
+
+    let status = Command::new(env::var_os("CARGO").unwrap_or("cargo".into()))
+        .args(["build", "-p", "cube_solver_ffi", "--lib", "--profile", profile])
+        .arg("--target-dir").arg(lib_dir.parent().unwrap())
+        .status()
+        .unwrap();
+    assert!(status.success());
+    lib_dir
+}
+
+#[test]
+fn header_up_to_date() {
+    let generated = fs::read_to_string(concat!(env!("OUT_DIR"), "/include/cube_solver.h")).unwrap();
+    let committed = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("include/cube_solver.h")).unwrap();
+    assert!(generated == committed, "include/cube_solver.h is out of date, build with CUBE_SOLVER_UPDATE_HEADER=1");
+}
+
+#[test]
+fn c_program() {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let lib_dir = build_lib();
+    let out = tempfile::tempdir().unwrap();
+    let exe = out.path().join("solve");
+
+    let status = Command::new("cc")
+        .arg(manifest.join("tests/c/solve.c"))
+        .arg("-I").arg(concat!(env!("OUT_DIR"), "/include"))
+        .arg("-L").arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lcube_solver_ffi")
+        .arg("-o").arg(&exe)
+        .status()
+        .expect("no C compiler");
+    assert!(status.success());
+
+    let mut cube = CubieCube::default();
+    cube.apply_moves(&[R1, U1, F2, L3, D1, B2, R3, F1, U2, L1]);
+    let scrambled = format!("{:?}", FaceCube::from_cubie(&cube));
+    let mut twisted = FaceCube::new();
+    twisted.faces.swap(8, 9);
+    twisted.faces.swap(9, 20);
+
+    // Shared with the other crates' tests and kept between runs
+    let tables = Path::new(env!("CARGO_TARGET_TMPDIR")).join("tables");
+    let output = Command::new(&exe).arg(&tables).arg(&scrambled).arg(format!("{:?}", twisted)).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let solution = String::from_utf8(output.stdout).unwrap();
+    cube.apply_moves(&parse_face_moves(&solution).unwrap());
+    assert!(cube.is_solved());
+}
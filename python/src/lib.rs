@@ -0,0 +1,359 @@
+//! Python bindings, built with maturin into a module called `cube_solver`
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use pyo3::exceptions::{PyOSError, PyRuntimeError, PyTimeoutError, PyValueError};
+use pyo3::prelude::*;
+
+use cube_solver::{
+    common::{Move, N_CORNERS, N_FLIP, N_SLICE_SORTED, N_TWIST, N_UD_EDGES},
+    cubie,
+    error::Error,
+    face,
+    notation::parse_face_moves,
+    search::{self, Limits},
+};
+
+fn to_py_err(e: Error) -> PyErr {
+    match e {
+        Error::Io(_) | Error::CorruptTable(_) | Error::InvalidTable(_) => PyOSError::new_err(e.to_string()),
+        Error::Timeout => PyTimeoutError::new_err(e.to_string()),
+        Error::Cancelled | Error::NoScramble(_) => PyRuntimeError::new_err(e.to_string()),
+        _ => PyValueError::new_err(e.to_string()),
+    }
+}
+
+fn check_range(name: &str, value: u16, n: usize) -> PyResult<()> {
+    if value as usize >= n {
+        return Err(PyValueError::new_err(format!("{} must be less than {}", name, n)));
+    }
+    Ok(())
+}
+
+/// `timeout` in seconds, which has to be a duration Rust can hold
+fn limits(timeout: Option<f64>) -> PyResult<Limits<'static>> {
+    let timeout = timeout.map(Duration::try_from_secs_f64).transpose()
+        .map_err(|e| PyValueError::new_err(format!("invalid timeout: {}", e)))?;
+    Ok(Limits { timeout, cancel: None })
+}
+
+/// Cube as permutations and orientations of the corners and edges
+#[pyclass(name = "CubieCube", eq)]
+#[derive(Clone, PartialEq)]
+struct CubieCube(cubie::CubieCube);
+
+#[pymethods]
+impl CubieCube {
+    /// Solved cube
+    #[new]
+    fn new() -> Self {
+        CubieCube(cubie::CubieCube::default())
+    }
+
+    /// Uniformly random solvable cube
+    #[staticmethod]
+    fn random() -> Self {
+        CubieCube(cubie::CubieCube::random(&mut rand::thread_rng()))
+    }
+
+    #[staticmethod]
+    fn from_facelets(facelets: &str) -> PyResult<Self> {
+        FaceCube::new(Some(facelets))?.to_cubie()
+    }
+
+    fn to_facelets(&self) -> String {
+        format!("{:?}", face::FaceCube::from_cubie(&self.0))
+    }
+
+    /// Face turns like `"R U2 F'"`
+    fn apply_moves(&mut self, moves: &str) -> PyResult<()> {
+        self.0.apply_moves(&parse_face_moves(moves).map_err(to_py_err)?);
+        Ok(())
+    }
+
+    fn multiply(&mut self, other: &CubieCube) {
+        self.0.multiply(&other.0);
+    }
+
+    fn __mul__(&self, other: &CubieCube) -> CubieCube {
+        CubieCube(self.0.clone() * other.0.clone())
+    }
+
+    fn inverse(&self) -> CubieCube {
+        CubieCube(self.0.inverse())
+    }
+
+    fn is_solved(&self) -> bool {
+        self.0.is_solved()
+    }
+
+    fn verify(&self) -> PyResult<()> {
+        self.0.verify().map_err(to_py_err)
+    }
+
+    fn get_twist(&self) -> u16 {
+        self.0.get_twist()
+    }
+
+    fn set_twist(&mut self, twist: u16) -> PyResult<()> {
+        check_range("twist", twist, N_TWIST)?;
+        self.0.set_twist(twist);
+        Ok(())
+    }
+
+    fn get_flip(&self) -> u16 {
+        self.0.get_flip()
+    }
+
+    fn set_flip(&mut self, flip: u16) -> PyResult<()> {
+        check_range("flip", flip, N_FLIP)?;
+        self.0.set_flip(flip);
+        Ok(())
+    }
+
+    fn get_slice(&self) -> u16 {
+        self.0.get_slice()
+    }
+
+    fn get_slice_sorted(&self) -> u16 {
+        self.0.get_slice_sorted()
+    }
+
+    fn set_slice_sorted(&mut self, slice_sorted: u16) -> PyResult<()> {
+        check_range("slice_sorted", slice_sorted, N_SLICE_SORTED)?;
+        self.0.set_slice_sorted(slice_sorted);
+        Ok(())
+    }
+
+    fn get_corners(&self) -> u16 {
+        self.0.get_corners()
+    }
+
+    fn set_corners(&mut self, corners: u16) -> PyResult<()> {
+        check_range("corners", corners, N_CORNERS)?;
+        self.0.set_corners(corners);
+        Ok(())
+    }
+
+    /// Only meaningful in phase 2, when the slice edges are in the slice
+    fn get_ud_edges(&self) -> u16 {
+        self.0.get_ud_edges()
+    }
+
+    fn set_ud_edges(&mut self, ud_edges: u16) -> PyResult<()> {
+        check_range("ud_edges", ud_edges, N_UD_EDGES)?;
+        self.0.set_ud_edges(ud_edges);
+        Ok(())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("CubieCube.from_facelets('{}')", self.to_facelets())
+    }
+}
+
+/// Cube as the 54 facelets, `UUUUUUUUURRR...`
+#[pyclass(name = "FaceCube", eq)]
+#[derive(Clone, PartialEq)]
+struct FaceCube(face::FaceCube);
+
+#[pymethods]
+impl FaceCube {
+    /// Solved cube, or parsed from a facelet string
+    #[new]
+    #[pyo3(signature = (facelets=None))]
+    fn new(facelets: Option<&str>) -> PyResult<Self> {
+        match facelets {
+            Some(f) => Ok(FaceCube(f.parse().map_err(|e: face::FaceletError| to_py_err(e.into()))?)),
+            None => Ok(FaceCube(face::FaceCube::new())),
+        }
+    }
+
+    /// Colour letters like `WWWWWWWWWRRR...`, the scheme is worked out from the centres
+    #[staticmethod]
+    fn from_colors(colors: &str) -> PyResult<Self> {
+        Ok(FaceCube(face::FaceCube::from_colors(colors).map_err(to_py_err)?))
+    }
+
+    #[staticmethod]
+    fn from_cubie(cube: &CubieCube) -> Self {
+        FaceCube(face::FaceCube::from_cubie(&cube.0))
+    }
+
+    fn to_cubie(&self) -> PyResult<CubieCube> {
+        Ok(CubieCube(self.0.to_cubie().map_err(to_py_err)?))
+    }
+
+    /// Unfolded net
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("FaceCube('{:?}')", self.0)
+    }
+}
+
+/// Moves that solve a cube
+#[pyclass(name = "Solution", frozen)]
+struct Solution {
+    #[pyo3(get)]
+    moves: Vec<String>,
+    /// Length in the solver's metric
+    #[pyo3(get)]
+    cost: usize,
+    /// Seconds the search took
+    #[pyo3(get)]
+    elapsed: f64,
+}
+
+#[pymethods]
+impl Solution {
+    fn __len__(&self) -> usize {
+        self.moves.len()
+    }
+
+    fn __str__(&self) -> String {
+        self.moves.join(" ")
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Solution('{}')", self.__str__())
+    }
+}
+
+/// Two-phase solver, loading the tables takes a while so keep it around
+#[pyclass(name = "Solver", frozen)]
+struct Solver(search::Solver);
+
+#[pymethods]
+impl Solver {
+    /// `tables_dir` is relative to `tables/` unless it's absolute
+    #[new]
+    #[pyo3(signature = (tables_dir=None))]
+    fn new(py: Python<'_>, tables_dir: Option<PathBuf>) -> PyResult<Self> {
+        let solver = py.allow_threads(|| search::Solver::new(tables_dir.as_deref())).map_err(to_py_err)?;
+        Ok(Solver(solver))
+    }
+
+    /// Solution of at most `max_length` moves or None. Other Python threads carry on while
+    /// it searches. Raises TimeoutError if `timeout` seconds pass first
+    #[pyo3(signature = (cube, max_length=20, timeout=None))]
+    fn solve(&self, py: Python<'_>, cube: &CubieCube, max_length: usize, timeout: Option<f64>) -> PyResult<Option<Solution>> {
+        let cube = cube.0.clone();
+        let limits = limits(timeout)?;
+        let report = py.allow_threads(|| self.0.solve_with(&cube, max_length, &limits)).map_err(to_py_err)?;
+
+        Ok(report.solution.map(|s| Solution {
+            moves: s.moves.iter().map(Move::to_string).collect(),
+            cost: s.cost,
+            elapsed: report.elapsed.as_secs_f64(),
+        }))
+    }
+
+    /// Random state scramble, returns the moves and the cube they make. Raises TimeoutError
+    /// if `timeout` seconds pass first
+    #[pyo3(signature = (timeout=None))]
+    fn scramble(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<(String, CubieCube)> {
+        let limits = limits(timeout)?;
+        let (cube, moves) = py.allow_threads(|| self.0.scramble(&mut rand::thread_rng(), &limits)).map_err(to_py_err)?;
+        let moves: Vec<String> = moves.iter().map(Move::to_string).collect();
+        Ok((moves.join(" "), CubieCube(cube)))
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "cube_solver")]
+fn py_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<CubieCube>()?;
+    m.add_class::<FaceCube>()?;
+    m.add_class::<Solution>()?;
+    m.add_class::<Solver>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::path::PathBuf;
+
+    use pyo3::ffi::c_str;
+    use pyo3::types::PyDict;
+
+    use super::*;
+
+    /// target/tmp/tables, shared with the other crates' tests and kept between runs
+    fn tables() -> PathBuf {
+        // The test binary is in target/<profile>/deps
+        env::current_exe().unwrap().ancestors().nth(3).unwrap().join("tmp/tables")
+    }
+
+    /// Runs Python `code` with the module imported as `cube_solver`
+    fn run(code: &std::ffi::CStr) {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let m = PyModule::new(py, "cube_solver").unwrap();
+            py_module(&m).unwrap();
+            let locals = PyDict::new(py);
+            locals.set_item("cube_solver", m).unwrap();
+            locals.set_item("TABLES", tables()).unwrap();
+            py.run(code, None, Some(&locals)).map_err(|e| e.display(py)).unwrap();
+        });
+    }
+
+    #[test]
+    fn cube_model() {
+        run(c_str!(r#"
+c = cube_solver.CubieCube()
+assert c.is_solved()
+c.apply_moves("R U F'")
+assert not c.is_solved()
+assert (c * c.inverse()).is_solved()
+assert cube_solver.CubieCube.from_facelets(c.to_facelets()) == c
+assert cube_solver.FaceCube.from_cubie(c).to_cubie() == c
+
+d = cube_solver.CubieCube()
+d.set_twist(c.get_twist())
+assert d.get_twist() == c.get_twist()
+try:
+    d.set_flip(5000)
+    assert False
+except ValueError:
+    pass
+
+f = cube_solver.FaceCube()
+assert repr(f).startswith("FaceCube('UUUUUUUUU")
+assert cube_solver.FaceCube.from_colors("WWWWWWWWWRRRRRRRRRGGGGGGGGGYYYYYYYYYOOOOOOOOOBBBBBBBBB") == f
+try:
+    cube_solver.FaceCube("UUU")
+    assert False
+except ValueError as e:
+    assert "54" in str(e)
+"#));
+    }
+
+    #[test]
+    fn solve_and_scramble() {
+        run(c_str!(r#"
+solver = cube_solver.Solver(str(TABLES))
+c = cube_solver.CubieCube()
+c.apply_moves("R U2 F' L D B2")
+s = solver.solve(c)
+assert len(s) <= 20 and s.elapsed >= 0
+c.apply_moves(str(s))
+assert c.is_solved()
+
+for bad in [-1, float("nan"), 1e300]:
+    try:
+        solver.solve(c, timeout=bad)
+        assert False
+    except ValueError as e:
+        assert "timeout" in str(e)
+
+moves, cube = solver.scramble(timeout=60)
+d = cube_solver.CubieCube()
+d.apply_moves(moves)
+assert d == cube
+"#));
+    }
+}
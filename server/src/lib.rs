@@ -0,0 +1,455 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use cube_solver::{
+    error::Error,
+    face::FaceCube,
+    search::{Limits, SolveReport, Solution, Solver},
+};
+
+/// Longest Kociemba request read, a facelet string is 54 letters plus maybe some whitespace
+const MAX_KOCIEMBA_REQUEST: usize = 1024;
+/// Longest request line and headers read for HTTP
+const MAX_HTTP_HEAD: u64 = 8 << 10;
+/// How long to wait for the client to finish sending before closing, see `finish`
+const LINGER: Duration = Duration::from_millis(100);
+/// Pause after a failed accept, usually out of file descriptors until some connections close
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Limits applied to every request
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Longest solution searched for, and the most a request can ask for
+    pub max_length: usize,
+    /// Time budget for a request, requests can ask for less but not more
+    pub timeout: Duration,
+    /// Connections open at once, anything over is turned away rather than queued
+    pub max_concurrent: usize,
+    /// Largest HTTP request body, anything bigger gets a 413
+    pub max_body: usize,
+    /// How long a read or write on a connection can take before it's dropped
+    pub io_timeout: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            max_length: 20,
+            timeout: Duration::from_secs(3),
+            max_concurrent: 4,
+            max_body: 16 << 10,
+            io_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Keeps one set of tables loaded and answers solve requests over both protocols
+pub struct Server {
+    solver: Solver,
+    config: Config,
+    active: AtomicUsize,
+}
+
+/// A slot in the concurrency limit, held for the whole connection and given back when dropped
+struct Permit(Arc<Server>);
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Server {
+    pub fn new(solver: Solver, config: Config) -> Arc<Self> {
+        Arc::new(Server { solver, config, active: AtomicUsize::new(0) })
+    }
+
+    fn permit(self: &Arc<Self>) -> Option<Permit> {
+        let max = self.config.max_concurrent;
+        self.active.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| if n < max { Some(n + 1) } else { None }).ok()?;
+        Some(Permit(Arc::clone(self)))
+    }
+
+    /// Solve a facelet string within the server's limits, `None` means the most allowed
+    pub fn solve(&self, facelets: &str, max_length: Option<usize>, timeout: Option<Duration>) -> Result<SolveReport, Error> {
+        let cube = facelets.parse::<FaceCube>()?.to_cubie()?;
+        let max_length = max_length.unwrap_or(self.config.max_length).min(self.config.max_length);
+        let timeout = timeout.unwrap_or(self.config.timeout).min(self.config.timeout);
+        self.solver.solve_with(&cube, max_length, &Limits { timeout: Some(timeout), cancel: None })
+    }
+
+    /// Kociemba's socket protocol: the client sends a facelet string and gets back one line,
+    /// either the solution like `R1 U2 F3 (3f)` or `Error: ...`, then the connection is closed
+    /// Only returns if the listener does, errors on a connection are logged
+    pub fn serve_kociemba(self: &Arc<Self>, listener: TcpListener) {
+        self.serve("kociemba", listener, |server, stream| server.handle_kociemba(stream), |mut stream| {
+            stream.write_all(b"Error: server busy\n")
+        });
+    }
+
+    /// JSON over HTTP, `POST /solve` with `{"facelets": ..., "max_length": ..., "timeout_ms": ...}`
+    /// and `GET /scramble`. One request per connection
+    /// Only returns if the listener does, errors on a connection are logged
+    pub fn serve_http(self: &Arc<Self>, listener: TcpListener) {
+        self.serve("http", listener, |server, stream| server.handle_http(stream), |stream| {
+            let (status, json) = error_json(503, "server busy");
+            write_http(stream, status, &json)
+        });
+    }
+
+    /// Accept loop shared by both protocols. Each connection gets a thread while there's a
+    /// permit for it, otherwise `busy` answers it straight away
+    fn serve(
+        self: &Arc<Self>,
+        protocol: &'static str,
+        listener: TcpListener,
+        handle: fn(&Server, &TcpStream) -> io::Result<()>,
+        busy: fn(&TcpStream) -> io::Result<()>,
+    ) {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("{}: accept failed: {}", protocol, e);
+                    thread::sleep(ACCEPT_BACKOFF);
+                    continue;
+                }
+            };
+            let timeouts = stream.set_read_timeout(Some(self.config.io_timeout))
+                .and_then(|_| stream.set_write_timeout(Some(self.config.io_timeout)));
+            if let Err(e) = timeouts {
+                eprintln!("{}: {}", protocol, e);
+                continue;
+            }
+
+            let Some(permit) = self.permit() else {
+                if let Err(e) = busy(&stream).and_then(|_| finish(&stream)) {
+                    eprintln!("{}: {}", protocol, e);
+                }
+                continue;
+            };
+            thread::spawn(move || {
+                let server = &permit.0;
+                if let Err(e) = handle(server, &stream).and_then(|_| finish(&stream)) {
+                    eprintln!("{}: {}", protocol, e);
+                }
+            });
+        }
+    }
+
+    fn handle_kociemba(&self, mut stream: &TcpStream) -> io::Result<()> {
+        // Clients don't always send a newline or close their end, so stop at 54 facelets
+        let mut data = Vec::new();
+        let mut buf = [0u8; 2048];
+        loop {
+            let n = stream.read(&mut buf)?;
+            data.extend_from_slice(&buf[..n]);
+            let letters = data.iter().filter(|b| b.is_ascii_alphabetic()).count();
+            if n == 0 || letters >= 54 || data.contains(&b'\n') || data.len() >= MAX_KOCIEMBA_REQUEST {
+                break;
+            }
+        }
+
+        let facelets: String = String::from_utf8_lossy(&data).chars().filter(|c| !c.is_whitespace()).collect();
+        let reply = match self.solve(&facelets, None, None) {
+            Ok(SolveReport { solution: Some(solution), .. }) => kociemba_string(&solution),
+            Ok(_) => format!("Error: no solution within {} moves", self.config.max_length),
+            Err(e) => format!("Error: {}", e),
+        };
+        stream.write_all(format!("{}\n", reply).as_bytes())
+    }
+
+    fn handle_http(&self, stream: &TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(stream).take(MAX_HTTP_HEAD);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+
+        let (status, json) = if reader.limit() == 0 {
+            error_json(431, "request headers too long")
+        } else if content_length > self.config.max_body {
+            error_json(413, &format!("request body over {} bytes", self.config.max_body))
+        } else {
+            reader.set_limit(content_length as u64);
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+
+            let mut parts = request_line.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some("POST"), Some("/solve")) => self.http_solve(&body),
+                (Some("GET"), Some("/scramble")) => self.http_scramble(),
+                _ => error_json(404, "not found"),
+            }
+        };
+        write_http(stream, status, &json)
+    }
+
+    fn http_solve(&self, body: &[u8]) -> (u16, String) {
+        let request: SolveRequest = match serde_json::from_slice(body) {
+            Ok(r) => r,
+            Err(e) => return error_json(400, &e.to_string()),
+        };
+
+        match self.solve(&request.facelets, request.max_length, request.timeout_ms.map(Duration::from_millis)) {
+            Ok(report) => (200, serde_json::to_string(&report).unwrap_or_default()),
+            Err(e) => error_json(status(&e), &e.to_string()),
+        }
+    }
+
+    fn http_scramble(&self) -> (u16, String) {
+        let limits = Limits { timeout: Some(self.config.timeout), cancel: None };
+        match self.solver.scramble(&mut rand::thread_rng(), &limits) {
+            Ok((cube, moves)) => {
+                let scramble = Scramble {
+                    scramble: Solution { moves, cost: 0 }.to_string(),
+                    facelets: format!("{:?}", FaceCube::from_cubie(&cube)),
+                };
+                (200, serde_json::to_string(&scramble).unwrap_or_default())
+            }
+            Err(e) => error_json(status(&e), &e.to_string()),
+        }
+    }
+}
+
+fn write_http(mut stream: &TcpStream, status: u16, json: &str) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Internal Server Error",
+    };
+    write!(stream, "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason, json.len(), json)
+}
+
+/// Close our end and read whatever the client still sends for a moment. Closing with unread
+/// data resets the connection, which can lose the reply before the client reads it
+fn finish(mut stream: &TcpStream) -> io::Result<()> {
+    stream.shutdown(Shutdown::Write)?;
+    let deadline = Instant::now() + LINGER;
+    let mut buf = [0u8; 2048];
+    while let Some(left) = deadline.checked_duration_since(Instant::now()).filter(|d| !d.is_zero()) {
+        stream.set_read_timeout(Some(left))?;
+        match stream.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct SolveRequest {
+    facelets: String,
+    max_length: Option<usize>,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct Scramble {
+    scramble: String,
+    facelets: String,
+}
+
+/// HTTP status for a failed solve
+fn status(e: &Error) -> u16 {
+    match e {
+        Error::InvalidFacelets(_) | Error::InvalidColors(_) | Error::InvalidNotation(_) | Error::InvalidCube(_) => 400,
+        Error::Unreachable => 422,
+        Error::Timeout | Error::Cancelled => 504,
+        Error::Io(_) | Error::CorruptTable(_) | Error::InvalidTable(_) | Error::OverBudget { .. } | Error::InvalidMetric(_)
+            | Error::NoScramble(_) => 500,
+    }
+}
+
+fn error_json(status: u16, message: &str) -> (u16, String) {
+    (status, serde_json::json!({ "error": message }).to_string())
+}
+
+/// Kociemba's format, powers as digits and the length at the end, e.g. `R1 U2 F3 (3f)`
+pub fn kociemba_string(solution: &Solution) -> String {
+    let faces = ["U", "R", "F", "D", "L", "B"];
+    let mut s: String = solution.moves.iter().map(|m| format!("{}{} ", faces[m.face()], m.power())).collect();
+    s.push_str(&format!("({}f)", solution.len()));
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::path::{Path, PathBuf};
+    use std::sync::OnceLock;
+
+    use cube_solver::{common::Move::*, cubie::CubieCube, notation::parse_face_moves};
+
+    use super::*;
+
+    /// target/tmp/tables, shared with the other crates' tests and kept between runs. The first
+    /// test to need the tables makes sure they're there
+    fn tables() -> &'static Path {
+        static DIR: OnceLock<PathBuf> = OnceLock::new();
+        DIR.get_or_init(|| {
+            // The test binary is in target/<profile>/deps
+            let dir = env::current_exe().unwrap().ancestors().nth(3).unwrap().join("tmp/tables");
+            Solver::new(Some(&dir)).unwrap();
+            dir
+        })
+    }
+
+    /// Both protocols on random ports
+    fn start(config: Config) -> (String, String) {
+        let (_, socket, http) = start_server(config);
+        (socket, http)
+    }
+
+    /// `start` that also gives back the server, to look at its state
+    fn start_server(config: Config) -> (Arc<Server>, String, String) {
+        let server = Server::new(Solver::new(Some(tables())).unwrap(), config);
+
+        let socket = TcpListener::bind("127.0.0.1:0").unwrap();
+        let http = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (socket_addr, http_addr) = (socket.local_addr().unwrap().to_string(), http.local_addr().unwrap().to_string());
+        let s = Arc::clone(&server);
+        thread::spawn(move || s.serve_kociemba(socket));
+        let s = Arc::clone(&server);
+        thread::spawn(move || s.serve_http(http));
+        (server, socket_addr, http_addr)
+    }
+
+    fn kociemba(addr: &str, facelets: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(facelets.as_bytes()).unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).unwrap();
+        reply
+    }
+
+    fn http(addr: &str, request: &str) -> (u16, serde_json::Value) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).unwrap();
+
+        let status = reply.split_whitespace().nth(1).unwrap().parse().unwrap();
+        let body = reply.split_once("\r\n\r\n").unwrap().1;
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    fn post_solve(addr: &str, json: &str) -> (u16, serde_json::Value) {
+        http(addr, &format!("POST /solve HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}", json.len(), json))
+    }
+
+    fn scrambled() -> CubieCube {
+        let mut cube = CubieCube::default();
+        cube.apply_moves(&[R1, U1, F2, L3, D1, B2, R3, F1, U2, L1]);
+        cube
+    }
+
+    #[test]
+    fn kociemba_protocol() {
+        let (socket, _) = start(Config::default());
+        let cube = scrambled();
+        let reply = kociemba(&socket, &format!("{:?}", FaceCube::from_cubie(&cube)));
+
+        let (moves, length) = reply.trim().rsplit_once(' ').unwrap();
+        let moves = parse_face_moves(moves).unwrap();
+        assert_eq!(length, format!("({}f)", moves.len()));
+        let mut c = cube.clone();
+        c.apply_moves(&moves);
+        assert!(c.is_solved());
+
+        // A newline ends the request as well
+        assert!(kociemba(&socket, "UUU\n").starts_with("Error: invalid facelet string"));
+    }
+
+    #[test]
+    fn http_solve() {
+        let (_, addr) = start(Config::default());
+        let facelets = format!("{:?}", FaceCube::from_cubie(&scrambled()));
+        let (status, json) = post_solve(&addr, &format!(r#"{{"facelets": "{}"}}"#, facelets));
+        assert_eq!(status, 200);
+        let moves = parse_face_moves(json["solution"]["moves"].as_str().unwrap()).unwrap();
+        let mut c = scrambled();
+        c.apply_moves(&moves);
+        assert!(c.is_solved());
+
+        // Twisted corner
+        let mut bad = FaceCube::new();
+        bad.faces.swap(8, 9);
+        bad.faces.swap(9, 20);
+        let (status, json) = post_solve(&addr, &format!(r#"{{"facelets": "{:?}"}}"#, bad));
+        assert_eq!(status, 400);
+        assert!(json["error"].as_str().unwrap().contains("twisted"));
+
+        let (status, _) = post_solve(&addr, "not json");
+        assert_eq!(status, 400);
+        let (status, _) = http(&addr, "GET /nowhere HTTP/1.1\r\n\r\n");
+        assert_eq!(status, 404);
+    }
+
+    #[test]
+    fn http_scramble() {
+        let (_, addr) = start(Config::default());
+        let (status, json) = http(&addr, "GET /scramble HTTP/1.1\r\n\r\n");
+        assert_eq!(status, 200);
+
+        let mut cube = CubieCube::default();
+        cube.apply_moves(&parse_face_moves(json["scramble"].as_str().unwrap()).unwrap());
+        assert_eq!(format!("{:?}", FaceCube::from_cubie(&cube)), json["facelets"].as_str().unwrap());
+    }
+
+    #[test]
+    fn limits() {
+        let (socket, addr) = start(Config { max_concurrent: 0, ..Config::default() });
+        assert_eq!(kociemba(&socket, &format!("{:?}", FaceCube::new())).trim(), "Error: server busy");
+        let (status, _) = post_solve(&addr, &format!(r#"{{"facelets": "{:?}"}}"#, FaceCube::new()));
+        assert_eq!(status, 503);
+
+        // An idle connection holds its slot
+        let (server, socket, addr) = start_server(Config { max_concurrent: 1, ..Config::default() });
+        let idle = TcpStream::connect(&addr).unwrap();
+        // Connecting doesn't wait for the server to accept
+        while server.active.load(Ordering::SeqCst) == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(kociemba(&socket, &format!("{:?}", FaceCube::new())).trim(), "Error: server busy");
+        drop(idle);
+
+        // Oversized requests
+        let (socket, addr) = start(Config { max_body: 100, ..Config::default() });
+        let (status, _) = post_solve(&addr, &format!(r#"{{"facelets": "{}"}}"#, " ".repeat(100)));
+        assert_eq!(status, 413);
+        let (status, _) = http(&addr, &format!("GET /scramble HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "a".repeat(10000)));
+        assert_eq!(status, 431);
+        assert!(kociemba(&socket, &"0".repeat(5000)).starts_with("Error: invalid facelet string"));
+
+        // Superflip needs 20 moves so 10 can't be enough
+        let (_, addr) = start(Config::default());
+        let superflip = format!("{:?}", FaceCube::from_cubie(&cube_solver::pattern::Pattern::Superflip.cube()));
+        let (status, json) = post_solve(&addr, &format!(r#"{{"facelets": "{}", "max_length": 10, "timeout_ms": 50}}"#, superflip));
+        assert!(status == 504 || (status == 200 && json["solution"].is_null()));
+    }
+}
@@ -0,0 +1,83 @@
+use std::env;
+use std::error::Error;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use cube_server::{Config, Server};
+use cube_solver::{common::{Move, N_MOVE}, metric::Metric, search::Solver};
+
+const USAGE: &str = "usage: cube_server [--tables DIR] [--memory-mb N] [--socket ADDR] [--http ADDR] [--max-length N] [--timeout-ms MS] [--max-concurrent N]
+
+  --socket ADDR        Kociemba protocol, default 127.0.0.1:8080
+  --http ADDR          JSON API, POST /solve and GET /scramble, default 127.0.0.1:8000
+  --tables DIR         table directory, relative to tables/
+  --memory-mb N        use the fastest tables that fit in N MB, default the 7.2 MB set
+  --max-length N       longest solution, default 20
+  --timeout-ms MS      time budget per request, default 3000
+  --max-concurrent N   solves at once, default 4";
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let mut tables = None;
+    let mut memory_mb: Option<usize> = None;
+    let mut socket = "127.0.0.1:8080".to_string();
+    let mut http = "127.0.0.1:8000".to_string();
+    let mut config = Config::default();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--help" || arg == "-h" {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        let value = args.next().ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE))?;
+        match arg.as_str() {
+            "--tables" => tables = Some(PathBuf::from(value)),
+            "--memory-mb" => memory_mb = Some(value.parse()?),
+            "--socket" => socket = value,
+            "--http" => http = value,
+            "--max-length" => config.max_length = value.parse()?,
+            "--timeout-ms" => config.timeout = Duration::from_millis(value.parse()?),
+            "--max-concurrent" => config.max_concurrent = value.parse()?,
+            _ => return Err(format!("unknown option {}\n{}", arg, USAGE).into()),
+        }
+    }
+
+    eprintln!("loading tables");
+    let solver = match memory_mb {
+        Some(mb) => {
+            let moves: Vec<Move> = (0..N_MOVE).map(Move::from_index).collect();
+            Solver::with_budget(tables.as_deref(), &moves, Metric::Half, mb << 20)?
+        }
+        None => Solver::new(tables.as_deref())?,
+    };
+    eprintln!("{}", solver.table_summary());
+    let server = Server::new(solver, config);
+
+    let socket = TcpListener::bind(&socket)?;
+    let http = TcpListener::bind(&http)?;
+    eprintln!("kociemba protocol on {}, http on {}", socket.local_addr()?, http.local_addr()?);
+
+    let s = Arc::clone(&server);
+    let threads = [
+        ("kociemba", thread::spawn(move || s.serve_kociemba(socket))),
+        ("http", thread::spawn(move || server.serve_http(http))),
+    ];
+    // The loops only end if one panics, and then it's better to exit than serve half the API
+    loop {
+        if let Some((protocol, _)) = threads.iter().find(|(_, t)| t.is_finished()) {
+            return Err(format!("{} server stopped", protocol).into());
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+}
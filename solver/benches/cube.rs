@@ -0,0 +1,72 @@
+// Coordinate and multiply benchmarks, `cargo bench --bench cube`
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{rngs::StdRng, SeedableRng};
+
+use cube_solver::{cubie::CubieCube, packed::PackedCube};
+
+fn random_cube() -> CubieCube {
+    CubieCube::random(&mut StdRng::seed_from_u64(45))
+}
+
+fn multiply(c: &mut Criterion) {
+    let a = random_cube();
+    let b = CubieCube::random(&mut StdRng::seed_from_u64(46));
+
+    c.bench_function("corner_multiply", |bench| bench.iter(|| {
+        let mut x = a.clone();
+        x.corner_multiply(black_box(&b));
+        x
+    }));
+    c.bench_function("edge_multiply", |bench| bench.iter(|| {
+        let mut x = a.clone();
+        x.edge_multiply(black_box(&b));
+        x
+    }));
+    c.bench_function("multiply", |bench| bench.iter(|| {
+        let mut x = a.clone();
+        x.multiply(black_box(&b));
+        x
+    }));
+    c.bench_function("inverse", |bench| bench.iter(|| black_box(&a).inverse()));
+
+    let (pa, pb) = (PackedCube::from(&a), PackedCube::from(&b));
+    c.bench_function("packed_multiply", |bench| bench.iter(|| black_box(pa) * black_box(pb)));
+    c.bench_function("packed_get_corners", |bench| bench.iter(|| black_box(pa).get_corners()));
+    c.bench_function("packed_get_slice_sorted", |bench| bench.iter(|| black_box(pa).get_slice_sorted()));
+}
+
+fn coordinates(c: &mut Criterion) {
+    let cube = random_cube();
+    let mut phase2 = CubieCube::default();
+    phase2.set_corners(12345);
+    phase2.set_ud_edges(23456);
+    phase2.set_slice_sorted(17);
+
+    let mut get = c.benchmark_group("get");
+    get.bench_function("twist", |b| b.iter(|| black_box(&cube).get_twist()));
+    get.bench_function("flip", |b| b.iter(|| black_box(&cube).get_flip()));
+    get.bench_function("slice", |b| b.iter(|| black_box(&cube).get_slice()));
+    get.bench_function("slice_sorted", |b| b.iter(|| black_box(&cube).get_slice_sorted()));
+    get.bench_function("u_edges", |b| b.iter(|| black_box(&cube).get_u_edges()));
+    get.bench_function("d_edges", |b| b.iter(|| black_box(&cube).get_d_edges()));
+    get.bench_function("corners", |b| b.iter(|| black_box(&cube).get_corners()));
+    get.bench_function("ud_edges", |b| b.iter(|| black_box(&phase2).get_ud_edges()));
+    get.finish();
+
+    // Values from the middle of each range
+    let mut set = c.benchmark_group("set");
+    let mut x = CubieCube::default();
+    set.bench_function("twist", |b| b.iter(|| x.set_twist(black_box(1093))));
+    set.bench_function("flip", |b| b.iter(|| x.set_flip(black_box(1024))));
+    set.bench_function("slice", |b| b.iter(|| x.set_slice(black_box(247))));
+    set.bench_function("slice_sorted", |b| b.iter(|| x.set_slice_sorted(black_box(5940))));
+    set.bench_function("u_edges", |b| b.iter(|| x.set_u_edges(black_box(5940))));
+    set.bench_function("d_edges", |b| b.iter(|| x.set_d_edges(black_box(5940))));
+    set.bench_function("corners", |b| b.iter(|| x.set_corners(black_box(20160))));
+    set.bench_function("ud_edges", |b| b.iter(|| x.set_ud_edges(black_box(20160))));
+    set.finish();
+}
+
+criterion_group!(benches, multiply, coordinates);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+// End to end solves of a fixed set of random cubes, `cargo bench --bench solve`
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::{rngs::StdRng, SeedableRng};
+
+use cube_solver::{common::Move, cubie::CubieCube, metric::Metric, pruning::TableSet, search::Solver};
+use strum::IntoEnumIterator;
+
+/// Same cubes every run so numbers can be compared between versions
+fn cubes() -> Vec<CubieCube> {
+    let mut rng = StdRng::seed_from_u64(2024);
+    (0..10).map(|_| CubieCube::random(&mut rng)).collect()
+}
+
+fn solve(c: &mut Criterion) {
+    let solver = Solver::new(None).unwrap();
+    let cubes = cubes();
+
+    let mut group = c.benchmark_group("solve_10_random");
+    group.sample_size(10);
+    for max_length in [24, 21, 20] {
+        group.bench_function(format!("max_{}", max_length), |b| b.iter(|| {
+            for cube in &cubes {
+                solver.solve(cube, max_length).unwrap().unwrap();
+            }
+        }));
+    }
+    group.finish();
+}
+
+/// Same solves with each set of pruning tables, where `TableSet::relative_time` comes from
+fn table_sets(c: &mut Criterion) {
+    let moves: Vec<Move> = Move::iter().collect();
+    let cubes = cubes();
+
+    let mut group = c.benchmark_group("table_set_10_random");
+    group.sample_size(10);
+    for tables in TableSet::ALL {
+        let solver = Solver::with_table_set(None, &moves, Metric::Half, tables).unwrap();
+        group.bench_function(tables.to_string(), |b| b.iter(|| {
+            for cube in &cubes {
+                solver.solve(cube, 21).unwrap().unwrap();
+            }
+        }));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, solve, table_sets);
+criterion_main!(benches);
@@ -0,0 +1,55 @@
+// Table generation and loading benchmarks, `cargo bench --bench tables`
+// Generation is timed by loading into an empty directory, so it includes writing the file
+
+use std::path::Path;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+use cube_solver::{error::Result, moves, search::Solver};
+
+type Load = fn(Option<&Path>) -> Result<Vec<u16>>;
+
+const MOVE_TABLES: [(&str, Load); 7] = [
+    ("twist", moves::load_twist_move_table),
+    ("flip", moves::load_flip_move_table),
+    ("slice_sorted", moves::load_ud_move_table),
+    ("corners", moves::load_corners_move_table),
+    ("ud_edges", moves::load_ud_edges_move_table),
+    ("u_edges", moves::load_u_edges_move_table),
+    ("d_edges", moves::load_d_edges_move_table),
+];
+
+fn generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate_move_table");
+    group.sample_size(10);
+    for (name, load) in MOVE_TABLES {
+        group.bench_function(name, |b| b.iter_batched(
+            || tempfile::tempdir().unwrap(),
+            |dir| load(Some(dir.path())).unwrap(),
+            BatchSize::PerIteration,
+        ));
+    }
+    group.finish();
+}
+
+fn load(c: &mut Criterion) {
+    // Make sure they're all there so only reading is timed
+    let solver = Solver::new(None).unwrap();
+    drop(solver);
+
+    let mut group = c.benchmark_group("load_move_table");
+    group.sample_size(10);
+    for (name, load) in MOVE_TABLES {
+        load(None).unwrap();
+        group.bench_function(name, |b| b.iter(|| load(None).unwrap()));
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("load_all");
+    group.sample_size(10);
+    group.bench_function("solver_new", |b| b.iter(|| Solver::new(None).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, generate, load);
+criterion_main!(benches);
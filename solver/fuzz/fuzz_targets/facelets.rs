@@ -0,0 +1,17 @@
+// Facelet and colour strings, `cargo fuzz run facelets`
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use cube_solver::face::FaceCube;
+
+fuzz_target!(|s: &str| {
+    if let Ok(cube) = s.parse::<FaceCube>() {
+        if let Ok(cc) = cube.to_cubie() {
+            assert!(FaceCube::from_cubie(&cc) == cube);
+        }
+    }
+    if let Ok(cube) = FaceCube::from_colors(s) {
+        let _ = cube.to_cubie();
+    }
+});
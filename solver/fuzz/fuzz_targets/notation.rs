@@ -0,0 +1,19 @@
+// Move sequences in any notation, `cargo fuzz run notation`
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use cube_solver::{cubie::CubieCube, notation::{normalise, parse_face_moves, parse_moves}};
+
+fuzz_target!(|s: &str| {
+    if let Ok(moves) = parse_moves(s) {
+        let (face_moves, _) = normalise(&moves);
+        let mut cube = CubieCube::default();
+        cube.apply_moves(&face_moves);
+        assert!(cube.verify().is_ok());
+    }
+    if let Ok(moves) = parse_face_moves(s) {
+        let mut cube = CubieCube::default();
+        cube.apply_moves(&moves);
+    }
+});
@@ -0,0 +1,48 @@
+// Reading move table files, `cargo fuzz run table_file`
+// The first byte picks the table, if the second is odd the rest is repeated up to the right
+// size so the contents get checked rather than just the length
+#![no_main]
+
+use std::{fs, path::Path};
+
+use libfuzzer_sys::fuzz_target;
+
+use cube_solver::{
+    common::{N_CORNERS, N_FLIP, N_MOVE, N_SLICE_SORTED, N_TWIST, N_UD_EDGES, N_U_EDGES},
+    error::Result,
+    moves,
+};
+
+type Load = fn(Option<&Path>) -> Result<Vec<u16>>;
+
+const TABLES: [(&str, usize, Load); 7] = [
+    ("move_twist", N_TWIST, moves::load_twist_move_table),
+    ("move_flip", N_FLIP, moves::load_flip_move_table),
+    ("move_slice_sorted", N_SLICE_SORTED, moves::load_ud_move_table),
+    ("move_corners", N_CORNERS, moves::load_corners_move_table),
+    ("move_ud_edges", N_UD_EDGES, moves::load_ud_edges_move_table),
+    ("move_u_edges", N_U_EDGES, moves::load_u_edges_move_table),
+    ("move_d_edges", N_U_EDGES, moves::load_d_edges_move_table),
+];
+
+fuzz_target!(|data: &[u8]| {
+    let [pick, pad, rest @ ..] = data else { return };
+    let (name, n, load) = TABLES[*pick as usize % TABLES.len()];
+
+    let bytes = if pad % 2 == 1 && !rest.is_empty() {
+        rest.iter().cycle().take(2*n*N_MOVE).copied().collect()
+    } else {
+        rest.to_vec()
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join(name), &bytes).unwrap();
+    if let Ok(table) = load(Some(dir.path())) {
+        // Following any entry has to stay inside the table
+        let mut c = 0;
+        for m in 0..N_MOVE {
+            c = table[N_MOVE*c + m] as usize;
+        }
+        assert!(c < n);
+    }
+});
@@ -0,0 +1,131 @@
+use std::num::NonZeroUsize;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+use crate::{
+    cubie::CubieCube,
+    error::Result,
+    face::FaceCube,
+    search::{Limits, SolveReport, Solver},
+};
+
+/// Something `Solver::solve_batch` can turn into a cube, a facelet string or a cube itself
+pub trait BatchInput {
+    fn to_cube(&self) -> Result<CubieCube>;
+}
+
+impl BatchInput for CubieCube {
+    fn to_cube(&self) -> Result<CubieCube> {
+        self.verify()?;
+        Ok(self.clone())
+    }
+}
+
+impl BatchInput for FaceCube {
+    fn to_cube(&self) -> Result<CubieCube> {
+        self.to_cubie()
+    }
+}
+
+/// `UUUUUUUUURRR...`, surrounding whitespace is ignored
+impl BatchInput for str {
+    fn to_cube(&self) -> Result<CubieCube> {
+        self.trim().parse::<FaceCube>()?.to_cubie()
+    }
+}
+
+impl BatchInput for String {
+    fn to_cube(&self) -> Result<CubieCube> {
+        self.as_str().to_cube()
+    }
+}
+
+impl<T: BatchInput + ?Sized> BatchInput for &T {
+    fn to_cube(&self) -> Result<CubieCube> {
+        (**self).to_cube()
+    }
+}
+
+impl Solver {
+    /// Solve every cube on all the cores, see `solve_batch_on`
+    pub fn solve_batch<I>(&self, cubes: I, max_length: usize, limits: &Limits) -> Vec<Result<SolveReport>>
+    where
+        I: IntoIterator,
+        I::IntoIter: Send,
+        I::Item: BatchInput,
+    {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        self.solve_batch_on(cubes, max_length, limits, threads)
+    }
+
+    /// Solve every cube with `solve_with` on `threads` threads sharing the tables
+    /// Results are in the same order as `cubes`, one that can't be read or solved doesn't
+    /// stop the others. `limits` apply to each cube separately
+    pub fn solve_batch_on<I>(&self, cubes: I, max_length: usize, limits: &Limits, threads: usize) -> Vec<Result<SolveReport>>
+    where
+        I: IntoIterator,
+        I::IntoIter: Send,
+        I::Item: BatchInput,
+    {
+        // Workers take the next cube as they finish, so slow ones don't hold up the rest
+        let cubes = Mutex::new(cubes.into_iter().enumerate());
+        let (tx, rx) = mpsc::channel();
+
+        thread::scope(|s| {
+            for _ in 0..threads.max(1) {
+                let tx = tx.clone();
+                let cubes = &cubes;
+                s.spawn(move || loop {
+                    let next = cubes.lock().unwrap().next();
+                    let Some((i, input)) = next else { break };
+                    let result = input.to_cube().and_then(|cube| self.solve_with(&cube, max_length, limits));
+                    if tx.send((i, result)).is_err() {
+                        break;
+                    }
+                });
+            }
+        });
+        drop(tx);
+
+        let mut results: Vec<(usize, Result<SolveReport>)> = rx.into_iter().collect();
+        results.sort_by_key(|&(i, _)| i);
+        results.into_iter().map(|(_, r)| r).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common::Move::*, error::Error, test_tables::solver};
+
+    #[test]
+    fn solves_in_order() {
+        let mut rng = rand::thread_rng();
+        let cubes: Vec<CubieCube> = (0..12).map(|_| CubieCube::random(&mut rng)).collect();
+        let results = solver().solve_batch_on(&cubes, 24, &Limits::default(), 3);
+
+        assert_eq!(results.len(), cubes.len());
+        for (cube, result) in cubes.iter().zip(results) {
+            let mut c = cube.clone();
+            c.apply_moves(&result.unwrap().solution.unwrap().moves);
+            assert!(c.is_solved());
+        }
+    }
+
+    #[test]
+    fn failures_are_per_cube() {
+        let mut cube = CubieCube::default();
+        cube.apply_moves(&[R1, U1, F3]);
+        let facelets = format!("{:?}", FaceCube::from_cubie(&cube));
+        let inputs = vec!["UUU".to_string(), facelets.clone(), "  ".to_string() + &facelets];
+
+        let results = solver().solve_batch(inputs, 20, &Limits::default());
+        assert!(matches!(results[0], Err(Error::InvalidFacelets(_))));
+        assert!(results[1].as_ref().unwrap().solution.is_some());
+        assert!(results[2].as_ref().unwrap().solution.is_some());
+
+        let mut twisted = CubieCube::default();
+        twisted.co[0] = 1;
+        assert!(matches!(solver().solve_batch([twisted], 20, &Limits::default())[0], Err(Error::InvalidCube(_))));
+    }
+}
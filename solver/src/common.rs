@@ -1,3 +1,6 @@
+use core::fmt::Display;
+
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
 // Consts
@@ -27,10 +30,15 @@ pub const N_MOVE: usize = 18; // Possible face moves
 pub const N_TWIST: usize = 2187; // Possible corner orientations, 3^7 (ignore 1 corner)
 pub const N_FLIP: usize = 2048; // Possible edge flips, 2^11 (ignore 1 edge)
 pub const N_SLICE_SORTED: usize = 11880; // Possible variations of the UD slice
+pub const N_SLICE: usize = 495; // Possible positions of the UD slice edges ignoring order, 12C4
+pub const N_PERM_4: usize = 24; // Permutations of 4 edges, slice_sorted in phase 2
+pub const N_CORNERS: usize = 40320; // Corner permutations, 8!
+pub const N_UD_EDGES: usize = 40320; // Permutations of the 8 U/D edges in phase 2, 8!
+pub const N_U_EDGES: usize = 11880; // Possible positions of the 4 U edges, 12!/8!
 
 
 // Enums
-#[derive(Clone, Copy, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
 pub enum Color {
     U = 0,
     R,
@@ -40,6 +48,64 @@ pub enum Color {
     B,
 }
 
+// Face turns, `3*face + k` with k = 0 (clockwise), 1 (half turn), 2 (anticlockwise)
+// Matches the indexing of the move tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+pub enum Move {
+    U1 = 0,
+    U2,
+    U3,
+    R1,
+    R2,
+    R3,
+    F1,
+    F2,
+    F3,
+    D1,
+    D2,
+    D3,
+    L1,
+    L2,
+    L3,
+    B1,
+    B2,
+    B3,
+}
+
+impl Move {
+    /// Index of the face being turned, matches `Color`
+    pub fn face(self) -> usize {
+        self as usize / 3
+    }
+
+    /// Number of clockwise quarter turns, 1..3
+    pub fn power(self) -> usize {
+        self as usize % 3 + 1
+    }
+
+    pub fn from_index(i: usize) -> Move {
+        Move::iter().nth(i).expect("move index out of range")
+    }
+
+    pub fn inverse(self) -> Move {
+        Move::from_index(3*self.face() + 2 - self as usize % 3)
+    }
+
+    /// Moves that keep the cube in the phase 2 subgroup <U, D, R2, F2, L2, B2>
+    pub fn is_phase2(self) -> bool {
+        matches!(self, Move::U1 | Move::U2 | Move::U3 | Move::D1 | Move::D2 | Move::D3
+            | Move::R2 | Move::F2 | Move::L2 | Move::B2)
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let face = ["U", "R", "F", "D", "L", "B"][self.face()];
+        let suffix = ["", "2", "'"][self.power() - 1];
+        write!(f, "{}{}", face, suffix)
+    }
+}
+
 // Done in order U/D, L/R, F/B, pretty arbitrary though
 #[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
 pub enum Corner {
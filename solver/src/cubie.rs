@@ -1,8 +1,11 @@
+use alloc::format;
+use rand::{seq::SliceRandom, Rng};
 use strum::IntoEnumIterator;
-use std::{fmt::Display};
-use std::ops::Mul;
+use core::fmt::Display;
+use core::ops::Mul;
 use crate::{
-    common::{Corner, Edge},
+    common::{Corner, Edge, Move},
+    error::Result,
     misc::{c_nk, rotate_left, rotate_right}
 };
 
@@ -62,7 +65,7 @@ pub const BASIC_MOVES: [CubieCube; 6] = [
 ];
 
 // Cube defined in terms of cubie permutations and orientations
-#[derive(PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct CubieCube {
     pub(crate) cp: CPerm,
     pub(crate) co: COrie,
@@ -185,7 +188,7 @@ impl CubieCube {
             flip /= 2;
         }
 
-        self.eo[Edge::BR as usize] = ((2 - fp % 2) % 2) as i8;
+        self.eo[Edge::BR as usize] = (2 - fp % 2) % 2;
     }
 
     /// UD Slice orientation, 0..495 phase 1, 0 phase 2
@@ -205,7 +208,31 @@ impl CubieCube {
         a
     }
 
-    pub fn set_slice(&mut self, idx: u16) { 
+    pub fn set_slice(&mut self, idx: u16) {
+        let slice_edge = [Ed::FR, Ed::FL, Ed::BL, Ed::BR];
+        let other_edge = [Ed::UR, Ed::UF, Ed::UL, Ed::UB, Ed::DR, Ed::DF, Ed::DL, Ed::DB];
+        let mut a = idx as u32; // Location of the slice edges
+
+        for e in Ed::iter().skip(1) {
+            self.ep[e as usize] = Ed::INV;
+        }
+
+        let mut x = 4;
+        for j in Ed::iter().skip(1) {
+            if a as i32 - c_nk(11 - j as u16, x) as i32 >= 0 {
+                self.ep[j as usize] = slice_edge[4 - x as usize];
+                a -= c_nk(11 - j as u16, x) as u32;
+                x -= 1;
+            }
+        }
+
+        let mut x = 0;
+        for j in Ed::iter().skip(1) {
+            if self.ep[j as usize] == Ed::INV {
+                self.ep[j as usize] = other_edge[x];
+                x += 1;
+            }
+        }
     }
 
     /// UD Slice, 0..11880 phase 1, 0..24 phase 2
@@ -281,17 +308,405 @@ impl CubieCube {
 
     }
 
-    /// Permutation of U edges (UR, UF, UL and UB)
+    /// Permutation of U edges (UR, UF, UL and UB), 0..11880 phase 1, 0..1680 phase 2
     pub fn get_u_edges(&self) -> u16 {
         let mut a = 0;
         let mut x = 0;
-        let mut edge4 = [0; 4];
+        let mut edge_4 = [0; 4];
+        let mut ep_mod = self.ep;
+
+        // Rotate so that the U edges are at the end like the slice edges are
+        for _ in 0..4 {
+            rotate_right(&mut ep_mod, 0, 11);
+        }
+
+        for j in (0..12).rev() {
+            if Edge::UR as u16 <= ep_mod[j] as u16 && ep_mod[j] as u16 <= Edge::UB as u16 {
+                a += c_nk(11 - j as u16, x + 1);
+                edge_4[3 - x as usize] = ep_mod[j] as u16;
+                x += 1;
+            }
+        }
 
-        12
+        let mut b = 0;
+        for j in (1..4).rev() {
+            let mut k = 0;
+            while edge_4[j as usize] != j {
+                rotate_left(&mut edge_4, 0, j as usize);
+                k += 1;
+            }
+            b = (j + 1)*b + k;
+        }
+
+        24*a + b
     }
-    
+
     pub fn set_u_edges(&mut self, idx: u16) {
+        let mut slice_edge = [Ed::UR, Ed::UF, Ed::UL, Ed::UB];
+        let other_edge = [Ed::DR, Ed::DF, Ed::DL, Ed::DB, Ed::FR, Ed::FL, Ed::BL, Ed::BR];
+        let mut b = idx as u32 % 24;
+        let mut a = idx as u32 / 24;
+
+        for e in Ed::iter().skip(1) {
+            self.ep[e as usize] = Ed::INV;
+        }
+
+        let mut j = 1;
+        while j < 4 {
+            let mut k = b % (j + 1);
+            b /= j + 1;
+            while k > 0 {
+                rotate_right(&mut slice_edge, 0, j as usize);
+                k -= 1
+            }
+            j += 1
+        }
+
+        let mut x = 4;
+        for j in Ed::iter().skip(1) {
+            if a as i32 - c_nk(11 - j as u16, x) as i32 >= 0 {
+                self.ep[j as usize] = slice_edge[4 - x as usize];
+                a -= c_nk(11 - j as u16, x) as u32;
+                x -= 1;
+            }
+        }
+
+        let mut x = 0;
+        for j in Ed::iter().skip(1) {
+            if self.ep[j as usize] == Ed::INV {
+                self.ep[j as usize] = other_edge[x];
+                x += 1;
+            }
+        }
+
+        // Undo the rotation from get_u_edges
+        for _ in 0..4 {
+            rotate_left(&mut self.ep, 0, 11);
+        }
+    }
+
+    /// Permutation of D edges (DR, DF, DL and DB), 0..11880 phase 1, 0..1680 phase 2
+    pub fn get_d_edges(&self) -> u16 {
+        let mut a = 0;
+        let mut x = 0;
+        let mut edge_4 = [0; 4];
+        let mut ep_mod = self.ep;
+
+        for _ in 0..4 {
+            rotate_right(&mut ep_mod, 0, 11);
+        }
+
+        for j in (0..12).rev() {
+            if Edge::DR as u16 <= ep_mod[j] as u16 && ep_mod[j] as u16 <= Edge::DB as u16 {
+                a += c_nk(11 - j as u16, x + 1);
+                edge_4[3 - x as usize] = ep_mod[j] as u16;
+                x += 1;
+            }
+        }
+
+        let mut b = 0;
+        for j in (1..4).rev() {
+            let mut k = 0;
+            while edge_4[j as usize] != j + 4 {
+                rotate_left(&mut edge_4, 0, j as usize);
+                k += 1;
+            }
+            b = (j + 1)*b + k;
+        }
+
+        24*a + b
+    }
+
+    pub fn set_d_edges(&mut self, idx: u16) {
+        let mut slice_edge = [Ed::DR, Ed::DF, Ed::DL, Ed::DB];
+        let other_edge = [Ed::FR, Ed::FL, Ed::BL, Ed::BR, Ed::UR, Ed::UF, Ed::UL, Ed::UB];
+        let mut b = idx as u32 % 24;
+        let mut a = idx as u32 / 24;
+
+        for e in Ed::iter().skip(1) {
+            self.ep[e as usize] = Ed::INV;
+        }
+
+        let mut j = 1;
+        while j < 4 {
+            let mut k = b % (j + 1);
+            b /= j + 1;
+            while k > 0 {
+                rotate_right(&mut slice_edge, 0, j as usize);
+                k -= 1
+            }
+            j += 1
+        }
+
+        let mut x = 4;
+        for j in Ed::iter().skip(1) {
+            if a as i32 - c_nk(11 - j as u16, x) as i32 >= 0 {
+                self.ep[j as usize] = slice_edge[4 - x as usize];
+                a -= c_nk(11 - j as u16, x) as u32;
+                x -= 1;
+            }
+        }
+
+        let mut x = 0;
+        for j in Ed::iter().skip(1) {
+            if self.ep[j as usize] == Ed::INV {
+                self.ep[j as usize] = other_edge[x];
+                x += 1;
+            }
+        }
+
+        for _ in 0..4 {
+            rotate_left(&mut self.ep, 0, 11);
+        }
+    }
+
+    /// Corner permutation, 0..40320
+    pub fn get_corners(&self) -> u16 {
+        let mut perm = self.cp;
+        let mut b = 0;
+
+        for j in (1..8).rev() { // Count the rotations needed to bring each corner home
+            let mut k = 0;
+            while perm[j] as usize != j {
+                rotate_left(&mut perm, 0, j);
+                k += 1;
+            }
+            b = (j as u16 + 1)*b + k;
+        }
+
+        b
+    }
+
+    pub fn set_corners(&mut self, idx: u16) {
+        let mut idx = idx as u32;
+        self.cp = CP_S;
+
+        for j in 0..8 {
+            let mut k = idx % (j as u32 + 1);
+            idx /= j as u32 + 1;
+            while k > 0 {
+                rotate_right(&mut self.cp, 0, j);
+                k -= 1;
+            }
+        }
+    }
+
+    /// Permutation of the 8 U and D edges, 0..40320 only defined in phase 2
+    pub fn get_ud_edges(&self) -> u16 {
+        let mut perm = [Ed::UR; 8];
+        perm.copy_from_slice(&self.ep[0..8]);
+        let mut b = 0;
+
+        for j in (1..8).rev() {
+            let mut k = 0;
+            while perm[j] as usize != j {
+                rotate_left(&mut perm, 0, j);
+                k += 1;
+            }
+            b = (j as u16 + 1)*b + k;
+        }
+
+        b
+    }
+
+    pub fn set_ud_edges(&mut self, idx: u16) {
+        let mut idx = idx as u32;
+        // Slice edges aren't touched
+        self.ep[0..8].copy_from_slice(&EP_S[0..8]);
+
+        for j in 0..8 {
+            let mut k = idx % (j as u32 + 1);
+            idx /= j as u32 + 1;
+            while k > 0 {
+                rotate_right(&mut self.ep, 0, j);
+                k -= 1;
+            }
+        }
+    }
+
+    /// Multiply corners and edges by another cube
+    pub fn multiply(&mut self, b: &Self) {
+        self.corner_multiply(b);
+        self.edge_multiply(b);
+    }
+
+    /// Apply a single face turn
+    pub fn apply_move(&mut self, m: Move) {
+        for _ in 0..m.power() {
+            self.multiply(&BASIC_MOVES[m.face()]);
+        }
+    }
+
+    /// Apply a sequence of face turns in order
+    pub fn apply_moves(&mut self, moves: &[Move]) {
+        for m in moves {
+            self.apply_move(*m);
+        }
+    }
+
+    /// Inverse cube, `self * self.inverse()` is solved
+    pub fn inverse(&self) -> CubieCube {
+        let mut d = CubieCube::default();
+
+        for e in Edge::iter().skip(1) {
+            d.ep[self.ep[e as usize] as usize] = e;
+        }
+        for e in Edge::iter().skip(1) {
+            d.eo[e as usize] = self.eo[d.ep[e as usize] as usize];
+        }
+
+        for c in Corner::iter() {
+            d.cp[self.cp[c as usize] as usize] = c;
+        }
+        for c in Corner::iter() {
+            let ori = self.co[d.cp[c as usize] as usize];
+            d.co[c as usize] = if ori >= 3 { // Mirrored corners are their own inverse
+                ori
+            } else {
+                (3 - ori) % 3
+            };
+        }
+
+        d
+    }
+
+    /// True if all corners and edges are home and oriented
+    pub fn is_solved(&self) -> bool {
+        *self == CubieCube::default()
+    }
+
+    /// Builds a cube from piece indices and orientations, checking it can be solved
+    pub fn from_arrays(cp: [u8; 8], co: [u8; 8], ep: [u8; 12], eo: [u8; 12]) -> Result<CubieCube> {
+        if cp.iter().any(|&c| c >= 8) {
+            return Err(CubieError::CornerPermutation.into());
+        }
+        if ep.iter().any(|&e| e >= 12) {
+            return Err(CubieError::EdgePermutation.into());
+        }
+
+        let mut cube = CubieCube::default();
+        for i in 0..8 {
+            cube.cp[i] = Corner::iter().nth(cp[i] as usize).unwrap();
+            cube.co[i] = co[i] as i8;
+        }
+        for i in 0..12 {
+            // Skip INV
+            cube.ep[i] = Edge::iter().nth(ep[i] as usize + 1).unwrap();
+            cube.eo[i] = eo[i] as i8;
+        }
+
+        cube.verify()?;
+        Ok(cube)
+    }
+
+    /// Checks the cube could be made by turning faces of a solved one
+    pub fn verify(&self) -> Result<()> {
+        let mut corners = [false; 8];
+        for &c in &self.cp {
+            if c as i8 >= 8 || corners[c as usize] {
+                return Err(CubieError::CornerPermutation.into());
+            }
+            corners[c as usize] = true;
+        }
+        let mut edges = [false; 12];
+        for &e in &self.ep {
+            if !(0..12).contains(&(e as i8)) || edges[e as usize] {
+                return Err(CubieError::EdgePermutation.into());
+            }
+            edges[e as usize] = true;
+        }
+
+        if self.co.iter().any(|&o| !(0..3).contains(&o)) || self.co.iter().map(|&o| o as i32).sum::<i32>() % 3 != 0 {
+            return Err(CubieError::Twist.into());
+        }
+        if self.eo.iter().any(|&o| !(0..2).contains(&o)) || self.eo.iter().map(|&o| o as i32).sum::<i32>() % 2 != 0 {
+            return Err(CubieError::Flip.into());
+        }
+        if self.corner_parity() != self.edge_parity() {
+            return Err(CubieError::Parity.into());
+        }
+
+        Ok(())
+    }
+
+    /// Uniformly random solvable cube
+    pub fn random(rng: &mut impl Rng) -> CubieCube {
+        let mut cube = CubieCube::default();
+        cube.cp.shuffle(rng);
+        cube.ep.shuffle(rng);
+        if cube.corner_parity() != cube.edge_parity() {
+            cube.ep.swap(0, 1);
+        }
+
+        // Last orientation makes the total work out
+        for i in 0..7 {
+            cube.co[i] = rng.gen_range(0..3);
+        }
+        cube.co[7] = (3 - cube.co[..7].iter().sum::<i8>() % 3) % 3;
+        for i in 0..11 {
+            cube.eo[i] = rng.gen_range(0..2);
+        }
+        cube.eo[11] = cube.eo[..11].iter().sum::<i8>() % 2;
+
+        cube
+    }
 
+    /// 1 if the corner permutation is odd
+    pub fn corner_parity(&self) -> u8 {
+        parity(&self.cp.map(|c| c as usize))
+    }
+
+    /// 1 if the edge permutation is odd
+    pub fn edge_parity(&self) -> u8 {
+        parity(&self.ep.map(|e| e as usize))
+    }
+}
+
+/// Counts inversions
+fn parity(perm: &[usize]) -> u8 {
+    let mut s = 0;
+    for i in 0..perm.len() {
+        for j in 0..i {
+            if perm[j] > perm[i] {
+                s += 1;
+            }
+        }
+    }
+    s % 2
+}
+
+/// Why a `CubieCube` can't be solved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubieError {
+    /// Some corner is missing or there twice
+    CornerPermutation,
+    /// Some edge is missing or there twice
+    EdgePermutation,
+    /// Corner orientations don't add up to a multiple of 3
+    Twist,
+    /// Odd number of flipped edges
+    Flip,
+    /// Corner and edge permutations have different parity, i.e. two pieces swapped
+    Parity,
+}
+
+impl Display for CubieError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            CubieError::CornerPermutation => "not every corner is there exactly once",
+            CubieError::EdgePermutation => "not every edge is there exactly once",
+            CubieError::Twist => "a corner is twisted",
+            CubieError::Flip => "an edge is flipped",
+            CubieError::Parity => "two pieces are swapped",
+        })
+    }
+}
+
+impl core::error::Error for CubieError {}
+
+impl Default for CubieCube {
+    fn default() -> Self {
+        CubieCube::new(None, None, None, None)
     }
 }
 
@@ -300,23 +715,22 @@ impl Mul for CubieCube {
     type Output = CubieCube;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        let c = self.clone();
-        c.clone().corner_multiply(&rhs);
-        c.clone().edge_multiply(&rhs);
+        let mut c = self;
+        c.multiply(&rhs);
         c
     }
 }
 
 impl Display for CubieCube {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         for i in 0..Corner::iter().len() {
-            f.write_str(format!("({:?}, {:?})", self.cp[i], self. co[i]).as_str()).unwrap();
+            f.write_str(format!("({:?}, {:?})", self.cp[i], self. co[i]).as_str())?;
         };
 
-        f.write_str("\n").unwrap();
+        f.write_str("\n")?;
 
         for i in 0..Edge::iter().skip(1).len() {
-            f.write_str(format!("({:?}, {:?})", self.ep[i], self. eo[i]).as_str()).unwrap();
+            f.write_str(format!("({:?}, {:?})", self.ep[i], self. eo[i]).as_str())?;
         };
 
         Ok(())
@@ -325,38 +739,209 @@ impl Display for CubieCube {
 
 #[cfg(test)]
 mod tests {
-    use core::num;
-
     use super::*;
+    use crate::{
+        common::{N_CORNERS, N_FLIP, N_PERM_4, N_SLICE, N_SLICE_SORTED, N_TWIST, N_UD_EDGES, N_U_EDGES},
+        error::Error,
+        strategies,
+    };
+    use proptest::prelude::*;
+
+    #[test]
+    fn verify() {
+        let mut cube = CubieCube::default();
+        cube.apply_moves(&[Move::R1, Move::U1, Move::F3]);
+        assert!(cube.verify().is_ok());
+
+        let mut twisted = cube.clone();
+        twisted.co[0] = (twisted.co[0] + 1) % 3;
+        assert!(matches!(twisted.verify(), Err(Error::InvalidCube(CubieError::Twist))));
+
+        let mut flipped = cube.clone();
+        flipped.eo[3] ^= 1;
+        assert!(matches!(flipped.verify(), Err(Error::InvalidCube(CubieError::Flip))));
+
+        let mut swapped = cube.clone();
+        swapped.ep.swap(0, 1);
+        assert!(matches!(swapped.verify(), Err(Error::InvalidCube(CubieError::Parity))));
+
+        assert!(matches!(CubieCube::from_arrays([0; 8], [0; 8], [0; 12], [0; 12]), Err(Error::InvalidCube(CubieError::CornerPermutation))));
+        let ep = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 12];
+        assert!(matches!(CubieCube::from_arrays([0, 1, 2, 3, 4, 5, 6, 7], [0; 8], ep, [0; 12]), Err(Error::InvalidCube(CubieError::EdgePermutation))));
+    }
+
+    #[test]
+    fn random_cubes_are_valid() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            assert!(CubieCube::random(&mut rng).verify().is_ok());
+        }
+    }
+
+    /// `set` then `get` gives back every value of a coordinate
+    fn round_trip(n: usize, set: fn(&mut CubieCube, u16), get: fn(&CubieCube) -> u16) {
+        let mut cube = CubieCube::default();
+        for i in 0..n as u16 {
+            set(&mut cube, i);
+            assert_eq!(get(&cube), i);
+        }
+    }
+
+    #[test]
+    fn get_set_twist() {
+        round_trip(N_TWIST, CubieCube::set_twist, CubieCube::get_twist);
+        let mut cube = CubieCube::default();
+        for i in 0..N_TWIST as u16 {
+            cube.set_twist(i);
+            assert!(cube.co.iter().map(|&o| o as i32).sum::<i32>() % 3 == 0, "twist {} isn't valid", i);
+        }
+    }
 
     #[test]
     fn get_set_flip() {
-        let test_twists = [0, 1, 2, 69, 420, 523, 1547, 2047];
-        for test_twist in test_twists {
-            let mut cube = CubieCube::new(None, None, None, None);
-            cube.set_flip(test_twist);
-            assert_eq!(cube.get_flip(), test_twist);
+        round_trip(N_FLIP, CubieCube::set_flip, CubieCube::get_flip);
+        let mut cube = CubieCube::default();
+        for i in 0..N_FLIP as u16 {
+            cube.set_flip(i);
+            assert!(cube.eo.iter().map(|&o| o as i32).sum::<i32>() % 2 == 0, "flip {} isn't valid", i);
+        }
+    }
+
+    #[test]
+    fn get_set_slice() {
+        round_trip(N_SLICE, CubieCube::set_slice, CubieCube::get_slice);
+    }
+
+    #[test]
+    fn get_set_corners() {
+        round_trip(N_CORNERS, CubieCube::set_corners, CubieCube::get_corners);
+    }
+
+    #[test]
+    fn get_set_ud_edges() {
+        round_trip(N_UD_EDGES, CubieCube::set_ud_edges, CubieCube::get_ud_edges);
+    }
+
+    #[test]
+    fn get_set_u_d_edges() {
+        round_trip(N_U_EDGES, CubieCube::set_u_edges, CubieCube::get_u_edges);
+        round_trip(N_U_EDGES, CubieCube::set_d_edges, CubieCube::get_d_edges);
+        assert_eq!(CubieCube::default().get_u_edges(), 1656);
+        assert_eq!(CubieCube::default().get_d_edges(), 0);
+    }
+
+    /// Every permutation coordinate gives a cube with each piece there exactly once
+    /// ud_edges leaves the slice edges alone so each one starts from a solved cube
+    #[test]
+    fn set_makes_permutations() {
+        type Set = fn(&mut CubieCube, u16);
+        let setters: [(usize, Set); 5] = [
+            (N_SLICE_SORTED, CubieCube::set_slice_sorted),
+            (N_U_EDGES, CubieCube::set_u_edges),
+            (N_U_EDGES, CubieCube::set_d_edges),
+            (N_CORNERS, CubieCube::set_corners),
+            (N_UD_EDGES, CubieCube::set_ud_edges),
+        ];
+        for (n, set) in setters {
+            for i in 0..n as u16 {
+                let mut cube = CubieCube::default();
+                set(&mut cube, i);
+                let mut cp = cube.cp.map(|c| c as usize);
+                let mut ep = cube.ep.map(|e| e as usize);
+                cp.sort();
+                ep.sort();
+                assert_eq!(cp, [0, 1, 2, 3, 4, 5, 6, 7]);
+                assert_eq!(ep, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+            }
+        }
+    }
+
+    #[test]
+    fn moves_have_order_four() {
+        let mut rng = rand::thread_rng();
+        let start = CubieCube::random(&mut rng);
+        for m in Move::iter() {
+            let mut cube = start.clone();
+            for _ in 0..4 {
+                cube.apply_move(m);
+            }
+            assert!(cube == start, "{} four times isn't the identity", m);
+
+            cube.apply_moves(&[m, m.inverse()]);
+            assert!(cube == start, "{} then {} isn't the identity", m, m.inverse());
         }
     }
 
+    #[test]
+    fn move_and_inverse() {
+        let mut cube = CubieCube::default();
+        cube.apply_moves(&[Move::R1, Move::U1, Move::F3]);
+        assert!(!cube.is_solved());
+        cube.apply_moves(&[Move::F1, Move::U3, Move::R3]);
+        assert!(cube.is_solved());
+    }
+
+    #[test]
+    fn inverse_and_multiply() {
+        let mut cube = CubieCube::default();
+        cube.apply_moves(&[Move::R1, Move::U1, Move::F3, Move::B2, Move::L1]);
+        assert!((cube.clone() * cube.inverse()).is_solved());
+        assert!((cube.inverse() * cube.clone()).is_solved());
+
+        let mut undone = cube.inverse();
+        undone.apply_moves(&[Move::R1, Move::U1, Move::F3, Move::B2, Move::L1]);
+        assert!(undone.is_solved());
+    }
+
     #[test]
     fn get_set_slice_sorted() {
-        let test_slice_sorted = [0, 1, 2, 69, 420, 523, 1547, 2047];
-        for case in test_slice_sorted {
-            let mut cube = CubieCube::new(None, None, None, None);
-            cube.set_slice_sorted(case);
-            //assert_eq!(cube.get_slice_sorted(), case);
-            println!("set: {}, get: {}", case, cube.get_slice_sorted());
-        }
-
-        let set = 420;
-        let mut cube = CubieCube::new(None,None,None,None);
-        cube.set_slice_sorted(set);
-        let ep = cube.ep;
-        println!("Set: {}, ep: {:?}", set, ep);
-
-        //let mut numbers = [1,2,3,4,5,6,7,8,9];
-        //rotate_right(&mut numbers, 0, 4);
-        //println!("numbers: {:?}", numbers);
+        round_trip(N_SLICE_SORTED, CubieCube::set_slice_sorted, CubieCube::get_slice_sorted);
+
+        // Slice edges in their places but out of order only changes the low part
+        let mut cube = CubieCube::default();
+        for i in 0..N_PERM_4 as u16 {
+            cube.set_slice_sorted(i);
+            assert!(cube.ep[8..].iter().all(|&e| e as i8 >= Edge::FR as i8));
+            assert_eq!(cube.get_slice(), 0);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_inverse(a in strategies::cube()) {
+            prop_assert!((a.clone() * a.inverse()).is_solved());
+            prop_assert!((a.inverse() * a.clone()).is_solved());
+            prop_assert_eq!(a.inverse().inverse(), a);
+        }
+
+        #[test]
+        fn prop_multiply_is_associative(a in strategies::cube(), b in strategies::cube(), c in strategies::cube()) {
+            prop_assert_eq!((a.clone() * b.clone()) * c.clone(), a * (b * c));
+        }
+
+        #[test]
+        fn prop_products_are_valid(a in strategies::cube(), b in strategies::cube()) {
+            prop_assert!((a * b).verify().is_ok());
+        }
+
+        /// The D3 multiply agrees with plain C3 addition for real cubes
+        #[test]
+        fn prop_corner_multiply_simple(a in strategies::cube(), b in strategies::cube()) {
+            let mut x = a.clone();
+            let mut y = a;
+            x.corner_multiply(&b);
+            y.corner_multiply_simple(&b);
+            prop_assert_eq!(x, y);
+        }
+
+        /// Undoing the moves backwards gets back to the start
+        #[test]
+        fn prop_moves_undo(a in strategies::cube(), moves in strategies::moves(30)) {
+            let mut cube = a.clone();
+            cube.apply_moves(&moves);
+            let undo: Vec<Move> = moves.iter().rev().map(|m| m.inverse()).collect();
+            cube.apply_moves(&undo);
+            prop_assert_eq!(cube, a);
+        }
     }
 }
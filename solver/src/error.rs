@@ -0,0 +1,107 @@
+use core::fmt::Display;
+#[cfg(feature = "std")]
+use std::{io, path::PathBuf};
+
+use crate::{common::Move, cubie::CubieError, face::FaceletError, notation::NotationError, scheme::SchemeError};
+
+/// Everything that can go wrong in the crate
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a table file failed
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    /// A table file is there but isn't the right size
+    #[cfg(feature = "std")]
+    CorruptTable(PathBuf),
+    /// A table passed to `Solver::from_tables` is the wrong size or has entries out of range
+    InvalidTable(&'static str),
+    /// Not even the smallest tables fit in the memory budget, see `Solver::with_budget`
+    OverBudget { budget: usize, needed: usize },
+    /// A `Metric::Custom` gives this move a cost of 0, which the search can't bound
+    InvalidMetric(Move),
+    /// The facelet string couldn't be read
+    InvalidFacelets(FaceletError),
+    /// The colour string or scheme couldn't be read
+    InvalidColors(SchemeError),
+    /// A move couldn't be read
+    InvalidNotation(NotationError),
+    /// The cube can't be solved at all, e.g. a twisted corner
+    InvalidCube(CubieError),
+    /// The cube can't be solved using the solver's move set
+    Unreachable,
+    /// The search ran out of time before finding a solution
+    Timeout,
+    /// The search was cancelled before finding a solution
+    Cancelled,
+    /// `Solver::scramble` found no solution of at most this length for its random cube
+    NoScramble(usize),
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            Error::Io(e) => write!(f, "table I/O failed: {}", e),
+            #[cfg(feature = "std")]
+            Error::CorruptTable(path) => write!(f, "table {} is corrupt", path.display()),
+            Error::InvalidTable(name) => write!(f, "table {} is invalid", name),
+            Error::OverBudget { budget, needed } => write!(f, "tables need {} bytes but the budget is {}", needed, budget),
+            Error::InvalidMetric(m) => write!(f, "{} costs 0 in the custom metric", m),
+            Error::InvalidFacelets(e) => write!(f, "invalid facelet string: {}", e),
+            Error::InvalidColors(e) => write!(f, "invalid colours: {}", e),
+            Error::InvalidNotation(e) => e.fmt(f),
+            Error::InvalidCube(e) => write!(f, "invalid cube: {}", e),
+            Error::Unreachable => f.write_str("cube is unreachable in this subgroup"),
+            Error::Timeout => f.write_str("no solution found in time"),
+            Error::Cancelled => f.write_str("search cancelled"),
+            Error::NoScramble(length) => write!(f, "no scramble found within {} moves", length),
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Error::Io(e) => Some(e),
+            Error::InvalidFacelets(e) => Some(e),
+            Error::InvalidColors(e) => Some(e),
+            Error::InvalidNotation(e) => Some(e),
+            Error::InvalidCube(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<FaceletError> for Error {
+    fn from(e: FaceletError) -> Self {
+        Error::InvalidFacelets(e)
+    }
+}
+
+impl From<SchemeError> for Error {
+    fn from(e: SchemeError) -> Self {
+        Error::InvalidColors(e)
+    }
+}
+
+impl From<NotationError> for Error {
+    fn from(e: NotationError) -> Self {
+        Error::InvalidNotation(e)
+    }
+}
+
+impl From<CubieError> for Error {
+    fn from(e: CubieError) -> Self {
+        Error::InvalidCube(e)
+    }
+}
@@ -1,6 +1,10 @@
-use std::fmt::{Display, Debug};
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::{Display, Debug};
+use core::str::FromStr;
+use strum::IntoEnumIterator;
 use crate::{common::{Color, CORNER_FACELET, CORNER_COLOR, EDGE_FACELET, EDGE_COLOR}, cubie::CubieCube};
 
+#[derive(Clone, PartialEq, Eq)]
 pub struct FaceCube {
     pub faces: [Color; 54],
 }
@@ -45,20 +49,136 @@ impl FaceCube {
     }
 
     pub fn solved_colors() -> [Color; 54] {
-        [
-            [Color::U; 9],
-            [Color::R; 9],
-            [Color::F; 9],
-            [Color::D; 9],
-            [Color::L; 9],
-            [Color::B; 9],
-        ].concat().try_into().unwrap_or_else(|_| panic!("nice"))
+        const FACES: [Color; 6] = [Color::U, Color::R, Color::F, Color::D, Color::L, Color::B];
+
+        let mut faces = [Color::U; 54];
+        for (i, face) in faces.iter_mut().enumerate() {
+            *face = FACES[i / 9];
+        }
+        faces
+    }
+
+    /// Works out which piece is where, the opposite of `from_cubie`
+    pub fn to_cubie(&self) -> crate::error::Result<CubieCube> {
+        for c in Color::iter() {
+            if self.faces[9*c as usize + 4] != c {
+                return Err(FaceletError::Centers.into());
+            }
+            if self.faces.iter().filter(|&&f| f == c).count() != 9 {
+                return Err(FaceletError::ColorCount(c).into());
+            }
+        }
+
+        let mut cp = [0; 8];
+        let mut co = [0; 8];
+        for i in 0..8 {
+            // The U or D sticker says how the corner is twisted
+            let fac = CORNER_FACELET[i].map(|f| self.faces[f as usize]);
+            let ori = fac.iter().position(|&c| c == Color::U || c == Color::D).ok_or(FaceletError::InvalidCorner(i))?;
+            let (col1, col2) = (fac[(ori + 1) % 3], fac[(ori + 2) % 3]);
+            let j = CORNER_COLOR.iter().position(|c| c[0] == fac[ori] && c[1] == col1 && c[2] == col2).ok_or(FaceletError::InvalidCorner(i))?;
+            cp[i] = j as u8;
+            co[i] = ori as u8;
+        }
+
+        let mut ep = [0; 12];
+        let mut eo = [0; 12];
+        for i in 0..12 {
+            let fac = EDGE_FACELET[i].map(|f| self.faces[f as usize]);
+            let (j, ori) = EDGE_COLOR.iter().enumerate().find_map(|(j, c)| {
+                if *c == fac {
+                    Some((j, 0))
+                } else if c[0] == fac[1] && c[1] == fac[0] {
+                    Some((j, 1))
+                } else {
+                    None
+                }
+            }).ok_or(FaceletError::InvalidEdge(i))?;
+            ep[i] = j as u8;
+            eo[i] = ori;
+        }
+
+        CubieCube::from_arrays(cp, co, ep, eo)
+    }
+
+    /// The unfolded net as a 9x12 grid, U on top, then L F R B, then D. `None` is a gap
+    pub fn net(&self) -> [[Option<Color>; 12]; 9] {
+        // Top left of each face in the grid, indexed by `Color`
+        const OFFSETS: [(usize, usize); 6] = [(0, 3), (3, 6), (3, 3), (6, 3), (3, 0), (3, 9)];
+
+        let mut net = [[None; 12]; 9];
+        for (i, &c) in self.faces.iter().enumerate() {
+            let (row, col) = OFFSETS[i / 9];
+            net[row + i % 9 / 3][col + i % 3] = Some(c);
+        }
+        net
+    }
+}
+
+impl Default for FaceCube {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FaceletError {
+    /// Not one of U, R, F, D, L, B
+    InvalidLetter(char),
+    /// Facelet strings are 54 letters
+    WrongLength(usize),
+    /// A colour isn't on exactly 9 facelets
+    ColorCount(Color),
+    /// Centres have to be U, R, F, D, L, B in that order
+    Centers,
+    /// The corner at this position has colours no corner has
+    InvalidCorner(usize),
+    /// The edge at this position has colours no edge has
+    InvalidEdge(usize),
+}
+
+impl Display for FaceletError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FaceletError::InvalidLetter(c) => write!(f, "invalid facelet `{}`", c),
+            FaceletError::WrongLength(n) => write!(f, "expected 54 facelets, got {}", n),
+            FaceletError::ColorCount(c) => write!(f, "{:?} isn't on 9 facelets", c),
+            FaceletError::Centers => f.write_str("centres aren't in URFDLB order"),
+            FaceletError::InvalidCorner(i) => write!(f, "corner {} doesn't exist", i),
+            FaceletError::InvalidEdge(i) => write!(f, "edge {} doesn't exist", i),
+        }
+    }
+}
+
+impl core::error::Error for FaceletError {}
+
+/// Reads the same string `Debug` prints, e.g. `UUUUUUUUURRR...`
+impl FromStr for FaceCube {
+    type Err = FaceletError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut faces = Vec::with_capacity(54);
+        for c in s.chars() {
+            faces.push(match c {
+                'U' => Color::U,
+                'R' => Color::R,
+                'F' => Color::F,
+                'D' => Color::D,
+                'L' => Color::L,
+                'B' => Color::B,
+                _ => return Err(FaceletError::InvalidLetter(c)),
+            });
+        }
+        let len = faces.len();
+        let faces = faces.try_into().map_err(|_| FaceletError::WrongLength(len))?;
+
+        Ok(FaceCube { faces })
     }
 }
 
 /// Just prints the list of facelets
 impl Debug for FaceCube {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         for c in self.faces.iter() {
             f.write_str(match c {
                 Color::U => "U",
@@ -67,7 +187,7 @@ impl Debug for FaceCube {
                 Color::D => "D",
                 Color::L => "L",
                 Color::B => "B",
-            }).unwrap();
+            })?;
         }
         
         Ok(())
@@ -76,7 +196,7 @@ impl Debug for FaceCube {
 
 /// User-friendly(er) 2D representation
 impl Display for FaceCube {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let s = format!("{:?}", self);
         let r: String = format!("   {}\n   {}\n   {}\n{}{}{}{}\n{}{}{}{} \n{}{}{}{}\n   {}\n   {}\n   {}\n",
                         &s[0..3], &s[3..6], &s[6..9], 
@@ -89,5 +209,80 @@ impl Display for FaceCube {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common::{Facelet, Move}, cubie::CubieError, error::Error, strategies};
+    use proptest::prelude::*;
+
+    #[test]
+    fn facelet_string_round_trip() {
+        let mut cc = CubieCube::default();
+        cc.apply_moves(&[Move::R1, Move::U1, Move::F3]);
+        let cube = FaceCube::from_cubie(&cc);
+        let s = format!("{:?}", cube);
+        assert!(s.parse::<FaceCube>().unwrap() == cube);
+
+        assert_eq!("UUU".parse::<FaceCube>().err(), Some(FaceletError::WrongLength(3)));
+        assert_eq!(s.replace('B', "X").parse::<FaceCube>().err(), Some(FaceletError::InvalidLetter('X')));
+    }
+
+    #[test]
+    fn to_cubie() {
+        let mut cc = CubieCube::default();
+        cc.apply_moves(&[Move::R1, Move::U1, Move::F3, Move::D2, Move::B1]);
+        assert!(FaceCube::from_cubie(&cc).to_cubie().unwrap() == cc);
+        assert!(FaceCube::new().to_cubie().unwrap().is_solved());
+
+        // Swapping two stickers of a corner mirrors it, no corner has its colours that way round
+        let mut cube = FaceCube::new();
+        cube.faces.swap(Facelet::U9 as usize, Facelet::R1 as usize);
+        assert!(matches!(cube.to_cubie(), Err(Error::InvalidFacelets(FaceletError::InvalidCorner(0)))));
+
+        // Cycling all three twists it
+
+        let mut cube = FaceCube::new();
+        cube.faces.swap(Facelet::U9 as usize, Facelet::R1 as usize);
+        cube.faces.swap(Facelet::R1 as usize, Facelet::F3 as usize);
+        assert!(matches!(cube.to_cubie(), Err(Error::InvalidCube(CubieError::Twist))));
 
+        // The side colours alone would pass for solved corners
+        let mut cube = FaceCube::new();
+        cube.faces.swap(Facelet::U9 as usize, Facelet::D3 as usize);
+        assert!(matches!(cube.to_cubie(), Err(Error::InvalidFacelets(FaceletError::InvalidCorner(_)))));
 
+        let mut cube = FaceCube::new();
+        cube.faces.swap(Facelet::U5 as usize, Facelet::R5 as usize);
+        assert!(matches!(cube.to_cubie(), Err(Error::InvalidFacelets(FaceletError::Centers))));
+
+        let mut cube = FaceCube::new();
+        cube.faces[Facelet::U1 as usize] = Color::D;
+        assert!(matches!(cube.to_cubie(), Err(Error::InvalidFacelets(FaceletError::ColorCount(Color::U)))));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_cubie_round_trip(cc in strategies::cube()) {
+            let cube = FaceCube::from_cubie(&cc);
+            prop_assert_eq!(cube.to_cubie().unwrap(), cc);
+            let s = format!("{:?}", cube);
+            prop_assert!(s.parse::<FaceCube>().unwrap() == cube);
+        }
+
+        /// Anything made of the right letters either gives a cube or an error
+        #[test]
+        fn prop_any_facelets(s in "[URFDLB]{54}") {
+            if let Ok(cc) = s.parse::<FaceCube>().unwrap().to_cubie() {
+                prop_assert!(cc.verify().is_ok());
+                prop_assert_eq!(format!("{:?}", FaceCube::from_cubie(&cc)), s);
+            }
+        }
+
+        #[test]
+        fn prop_any_string(s in "\\PC*") {
+            if let Ok(cube) = s.parse::<FaceCube>() {
+                let _ = cube.to_cubie();
+            }
+        }
+    }
+}
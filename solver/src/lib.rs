@@ -1,8 +1,40 @@
+// Everything but the table files, terminal and threads works without std, see the `std` feature
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod batch;
 pub mod common;
 pub mod face;
 pub mod cubie;
+pub mod error;
 pub mod moves;
+pub mod metric;
 pub mod misc;
+pub mod notation;
+pub mod packed;
+pub mod partial;
+pub mod pattern;
+pub mod pruning;
+#[cfg(feature = "std")]
+pub mod render;
+pub mod scheme;
+pub mod search;
+pub mod simd;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(test)]
+mod strategies;
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(feature = "std")]
+pub mod svg;
+pub mod symmetry;
+#[cfg(feature = "std")]
+pub mod tables;
+#[cfg(all(test, feature = "std"))]
+mod test_tables;
 
 pub fn add(left: usize, right: usize) -> usize {
     left + right
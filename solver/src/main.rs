@@ -0,0 +1,352 @@
+use std::env;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
+
+use rand::{rngs::StdRng, SeedableRng};
+use strum::IntoEnumIterator;
+
+use cube_solver::{
+    common::Move,
+    metric::Metric,
+    search::{Limits, SolveReport, Solver},
+    stats::Stats,
+    tables::{self, TableStatus},
+};
+
+const USAGE: &str = "usage: cube batch [FILE] [--format csv|jsonl] [OPTIONS]
+       cube stats [--count N] [--seed N] [OPTIONS]
+       cube tables check [--tables DIR]
+
+  batch                solve one facelet string per line of FILE, or stdin if there's no FILE
+                       blank lines and lines starting with # are skipped
+  --format FORMAT      csv (default) or jsonl
+
+  stats                solve random cubes and report solution lengths, times and work done
+  --count N            cubes to solve, default 100
+  --seed N             random seed, default random
+
+  tables check         compare the tables in DIR with the digests they should have
+
+options:
+  --max-length N       longest solution, default 20
+  --timeout-ms MS      time budget per cube, default none
+  --threads N          default one per core for batch and 1 for stats
+  --tables DIR         table directory, relative to tables/
+  --memory-mb N        use the fastest tables that fit in N MB, default the 7.2 MB set";
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        Some("batch") => batch(args),
+        Some("stats") => stats(args),
+        Some("tables") if args.next().as_deref() == Some("check") => tables_check(args),
+        Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Csv,
+    JsonLines,
+}
+
+/// Calls `f` with each `--option value` pair, and with `""` for arguments that aren't options
+/// `f` returns false for anything it doesn't know
+fn parse_args(mut args: impl Iterator<Item = String>, mut f: impl FnMut(&str, String) -> Result<bool, Box<dyn Error>>) -> Result<(), Box<dyn Error>> {
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            if !f("", arg.clone())? {
+                return Err(format!("unexpected argument {}\n{}", arg, USAGE).into());
+            }
+            continue;
+        }
+        let value = args.next().ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE))?;
+        if !f(&arg, value)? {
+            return Err(format!("unknown option {}\n{}", arg, USAGE).into());
+        }
+    }
+    Ok(())
+}
+
+fn stats(args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut count = 100;
+    let mut seed = None;
+    let mut max_length = 20;
+    let mut timeout = None;
+    let mut threads = 1;
+    let mut tables = None;
+    let mut memory_mb = None;
+
+    parse_args(args, |arg, value| {
+        match arg {
+            "--count" => count = value.parse()?,
+            "--seed" => seed = Some(value.parse()?),
+            "--max-length" => max_length = value.parse()?,
+            "--timeout-ms" => timeout = Some(Duration::from_millis(value.parse()?)),
+            "--threads" => threads = value.parse()?,
+            "--tables" => tables = Some(PathBuf::from(value)),
+            "--memory-mb" => memory_mb = Some(value.parse()?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    })?;
+
+    let solver = load_solver(tables.as_deref(), memory_mb)?;
+    let mut rng = match seed {
+        Some(s) => StdRng::seed_from_u64(s),
+        None => StdRng::from_entropy(),
+    };
+    let limits = Limits { timeout, cancel: None };
+    print!("{}", Stats::random(&solver, count, max_length, &limits, threads, &mut rng));
+
+    Ok(())
+}
+
+/// Default tables, or the fastest that fit in `memory_mb` which are logged since they're picked
+fn load_solver(tables: Option<&Path>, memory_mb: Option<usize>) -> Result<Solver, Box<dyn Error>> {
+    let Some(mb) = memory_mb else {
+        return Ok(Solver::new(tables)?);
+    };
+    let moves: Vec<Move> = Move::iter().collect();
+    let solver = Solver::with_budget(tables, &moves, Metric::Half, mb << 20)?;
+    eprintln!("{}", solver.table_summary());
+    Ok(solver)
+}
+
+fn tables_check(args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut tables = None;
+    parse_args(args, |arg, value| {
+        match arg {
+            "--tables" => tables = Some(PathBuf::from(value)),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    })?;
+
+    let mut bad = 0;
+    for (name, status) in tables::check(tables.as_deref())? {
+        match status {
+            TableStatus::Ok => println!("{:<26} ok", name),
+            TableStatus::Missing => println!("{:<26} missing, generated when needed", name),
+            TableStatus::Mismatch(digest) => {
+                println!("{:<26} MISMATCH {}", name, digest);
+                bad += 1;
+            }
+        }
+    }
+
+    if bad > 0 {
+        return Err(format!("{} table(s) don't match, delete them to have them generated again", bad).into());
+    }
+    Ok(())
+}
+
+fn batch(args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut file = None;
+    let mut format = Format::Csv;
+    let mut max_length = 20;
+    let mut timeout = None;
+    let mut threads = None;
+    let mut tables = None;
+    let mut memory_mb = None;
+
+    parse_args(args, |arg, value| {
+        match arg {
+            "" => file = Some(PathBuf::from(value)),
+            "--format" => format = match value.as_str() {
+                "csv" => Format::Csv,
+                "jsonl" => Format::JsonLines,
+                _ => return Err(format!("unknown format {}", value).into()),
+            },
+            "--max-length" => max_length = value.parse()?,
+            "--timeout-ms" => timeout = Some(Duration::from_millis(value.parse()?)),
+            "--threads" => threads = Some(value.parse()?),
+            "--tables" => tables = Some(PathBuf::from(value)),
+            "--memory-mb" => memory_mb = Some(value.parse()?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    })?;
+
+    let input: Box<dyn BufRead> = match &file {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => Box::new(io::stdin().lock()),
+    };
+    let lines = read_lines(input)?;
+
+    let solver = load_solver(tables.as_deref(), memory_mb)?;
+    let limits = Limits { timeout, cancel: None };
+    let cubes = lines.iter().filter(|(_, _, error)| error.is_none()).map(|(_, l, _)| l);
+    let mut results = match threads {
+        Some(n) => solver.solve_batch_on(cubes, max_length, &limits, n),
+        None => solver.solve_batch(cubes, max_length, &limits),
+    }.into_iter();
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    if format == Format::Csv {
+        writeln!(out, "line,input,status,length,cost,time_ms,nodes,solution,error")?;
+    }
+    for (line, input, error) in &lines {
+        let row = match error {
+            Some(e) => Row { line: *line, input, report: None, error: Some(e.clone()) },
+            None => Row::new(*line, input, results.next().expect("a result for every cube")),
+        };
+        match format {
+            Format::Csv => row.write_csv(&mut out)?,
+            Format::JsonLines => row.write_json(&mut out)?,
+        }
+    }
+    out.flush()?;
+
+    Ok(())
+}
+
+/// One line of output
+/// Line number, the line and an error if it isn't UTF-8, for the lines that aren't blank or
+/// comments. Line numbers count from 1 and include the skipped lines. A line that isn't UTF-8
+/// gets an error row rather than stopping the run
+fn read_lines(input: impl BufRead) -> io::Result<Vec<(usize, String, Option<String>)>> {
+    let mut lines = Vec::new();
+    for (i, line) in input.split(b'\n').enumerate() {
+        let (line, error) = match String::from_utf8(line?) {
+            Ok(line) => (line, None),
+            Err(e) => (String::from_utf8_lossy(e.as_bytes()).into_owned(), Some(format!("line isn't UTF-8: {}", e.utf8_error()))),
+        };
+        let line = line.trim();
+        if !line.is_empty() && !line.starts_with('#') {
+            lines.push((i + 1, line.to_string(), error));
+        }
+    }
+    Ok(lines)
+}
+
+struct Row<'a> {
+    line: usize,
+    input: &'a str,
+    report: Option<SolveReport>,
+    error: Option<String>,
+}
+
+impl<'a> Row<'a> {
+    fn new(line: usize, input: &'a str, result: cube_solver::error::Result<SolveReport>) -> Self {
+        match result {
+            Ok(report) => Row { line, input, report: Some(report), error: None },
+            Err(e) => Row { line, input, report: None, error: Some(e.to_string()) },
+        }
+    }
+
+    fn status(&self) -> &'static str {
+        match &self.report {
+            Some(r) if r.solution.is_some() => "ok",
+            Some(_) => "no_solution",
+            None => "error",
+        }
+    }
+
+    fn write_csv(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "{},{},{},", self.line, csv_field(self.input), self.status())?;
+        match &self.report {
+            Some(r) => {
+                match &r.solution {
+                    Some(s) => write!(out, "{},{},", s.len(), s.cost)?,
+                    None => write!(out, ",,")?,
+                }
+                write!(out, "{:.3},{},", r.elapsed.as_secs_f64() * 1000.0, r.stats.nodes())?;
+                let solution = r.solution.as_ref().map(|s| s.to_string()).unwrap_or_default();
+                writeln!(out, "{},", solution)
+            }
+            None => writeln!(out, ",,,,,{}", csv_field(self.error.as_deref().unwrap_or_default())),
+        }
+    }
+
+    fn write_json(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, r#"{{"line":{},"input":{},"status":"{}""#, self.line, json_string(self.input), self.status())?;
+        if let Some(r) = &self.report {
+            if let Some(s) = &r.solution {
+                write!(out, r#","length":{},"cost":{},"solution":"{}""#, s.len(), s.cost, s)?;
+            }
+            write!(out, r#","time_ms":{:.3},"nodes":{}"#, r.elapsed.as_secs_f64() * 1000.0, r.stats.nodes())?;
+        }
+        if let Some(e) = &self.error {
+            write!(out, r#","error":{}"#, json_string(e))?;
+        }
+        writeln!(out, "}}")
+    }
+}
+
+/// Quoted if it has anything that would break the row
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows() {
+        let row = Row::new(3, "UUU", Err(cube_solver::error::Error::Timeout));
+        let mut csv = Vec::new();
+        row.write_csv(&mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "3,UUU,error,,,,,,no solution found in time\n");
+
+        let mut json = Vec::new();
+        row.write_json(&mut json).unwrap();
+        assert_eq!(String::from_utf8(json).unwrap(), "{\"line\":3,\"input\":\"UUU\",\"status\":\"error\",\"error\":\"no solution found in time\"}\n");
+    }
+
+    #[test]
+    fn lines() {
+        let input: &[u8] = b"# cubes\r\nUUU\r\n\n\xff RRR\nFFF";
+        let lines = read_lines(input).unwrap();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], (2, "UUU".to_string(), None));
+        assert_eq!((lines[1].0, lines[1].1.as_str()), (4, "\u{fffd} RRR"));
+        assert!(lines[1].2.as_ref().unwrap().starts_with("line isn't UTF-8"));
+        assert_eq!(lines[2], (5, "FFF".to_string(), None));
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+        assert_eq!(csv_field("abc"), "abc");
+        assert_eq!(json_string("a\"b\\\n\u{1}"), "\"a\\\"b\\\\\\n\\u0001\"");
+    }
+}
@@ -0,0 +1,165 @@
+use alloc::{format, string::{String, ToString}, vec::Vec};
+use arrayvec::ArrayVec;
+use strum::IntoEnumIterator;
+
+use crate::{
+    common::{Move, N_MOVE},
+    error::{Error, Result},
+};
+
+/// How the length of a solution is counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Metric {
+    /// Every face turn counts 1, the usual one
+    #[default]
+    Half,
+    /// Quarter turns count 1, half turns 2
+    Quarter,
+    /// Like `Half` but turning opposite faces the opposite way (`R L'`, `R2 L2`) is a slice move
+    /// and counts 1
+    Slice,
+    /// Any turns of the two faces on one axis done together count 1
+    Axial,
+    /// Cost for each move, indexed like `Move`. Opposite face turns add up. Costs must be at
+    /// least 1 (see `check`), e.g. robot timings for quarter and half turns
+    Custom([u8; N_MOVE]),
+}
+
+/// One or two face turns on the same axis, e.g. `R`, `L2` or `R L'`
+/// Opposite faces are always in canonical order, U before D etc.
+#[derive(Debug, Clone)]
+pub struct AxisMove {
+    pub moves: ArrayVec<usize, 2>,
+    /// Wider than the custom costs so two of them can be added up
+    pub cost: u16,
+}
+
+impl AxisMove {
+    /// Axis the move is on, 0 = U/D, 1 = R/L, 2 = F/B
+    pub fn axis(&self) -> usize {
+        Move::from_index(self.moves[0]).face() % 3
+    }
+
+    /// True if all the face turns are phase 2 moves
+    pub fn is_phase2(&self) -> bool {
+        self.moves.iter().all(|&m| Move::from_index(m).is_phase2())
+    }
+}
+
+impl Metric {
+    /// Check every move costs something, a free move would let the search go on forever
+    pub fn check(&self) -> Result<()> {
+        match self {
+            Metric::Custom(costs) => match costs.iter().position(|&c| c == 0) {
+                Some(i) => Err(Error::InvalidMetric(Move::from_index(i))),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Cost of a single face turn
+    fn move_cost(&self, m: Move) -> u16 {
+        match self {
+            Metric::Half | Metric::Slice | Metric::Axial => 1,
+            Metric::Quarter => if m.power() == 2 { 2 } else { 1 },
+            Metric::Custom(costs) => costs[m as usize] as u16,
+        }
+    }
+
+    /// Cost of turning two opposite faces together
+    fn pair_cost(&self, a: Move, b: Move) -> u16 {
+        match self {
+            Metric::Slice if a.power() + b.power() == 4 => 1,
+            Metric::Axial => 1,
+            _ => self.move_cost(a) + self.move_cost(b),
+        }
+    }
+
+    /// Cost of a sequence of face turns, turns of opposite faces next to each other are
+    /// counted together
+    pub fn cost(&self, moves: &[Move]) -> usize {
+        let mut total = 0;
+        let mut i = 0;
+        while i < moves.len() {
+            let a = moves[i];
+            match moves.get(i + 1) {
+                Some(&b) if b.face() != a.face() && b.face() % 3 == a.face() % 3 => {
+                    total += self.pair_cost(a, b) as usize;
+                    i += 2;
+                }
+                _ => {
+                    total += self.move_cost(a) as usize;
+                    i += 1;
+                }
+            }
+        }
+        total
+    }
+
+    /// All the axis moves that can be made from `allowed` face turns, with their cost
+    pub fn axis_moves(&self, allowed: &[usize]) -> Vec<AxisMove> {
+        let mut axis_moves = Vec::new();
+
+        for a in Move::iter().filter(|&m| allowed.contains(&(m as usize))) {
+            let mut single = ArrayVec::new();
+            single.push(a as usize);
+            axis_moves.push(AxisMove { moves: single, cost: self.move_cost(a) });
+
+            // Pair with the opposite face, only from the first of the two
+            if a.face() >= 3 {
+                continue;
+            }
+            for b in Move::iter().filter(|&m| allowed.contains(&(m as usize)) && m.face() == a.face() + 3) {
+                let mut pair = ArrayVec::new();
+                pair.push(a as usize);
+                pair.push(b as usize);
+                axis_moves.push(AxisMove { moves: pair, cost: self.pair_cost(a, b) });
+            }
+        }
+
+        axis_moves
+    }
+
+    /// Suffix to keep pruning tables for different metrics apart, empty for `Half`
+    pub fn suffix(&self) -> String {
+        match self {
+            Metric::Half => String::new(),
+            Metric::Quarter => "_qtm".to_string(),
+            Metric::Slice => "_stm".to_string(),
+            Metric::Axial => "_atm".to_string(),
+            Metric::Custom(costs) => {
+                let hex: String = costs.iter().map(|c| format!("{:02x}", c)).collect();
+                format!("_c{}", hex)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Move::*;
+
+    #[test]
+    fn sequence_costs() {
+        let moves = [R1, L3, U2, F1, B1, D2];
+        assert_eq!(Metric::Half.cost(&moves), 6);
+        assert_eq!(Metric::Quarter.cost(&moves), 8);
+        assert_eq!(Metric::Slice.cost(&moves), 5); // R L' is M'
+        assert_eq!(Metric::Axial.cost(&moves), 4);
+
+        let mut costs = [3; N_MOVE];
+        for m in [U2, R2, F2, D2, L2, B2] {
+            costs[m as usize] = 5;
+        }
+        assert_eq!(Metric::Custom(costs).cost(&moves), 3 + 3 + 5 + 3 + 3 + 5);
+    }
+
+    #[test]
+    fn axis_move_count() {
+        let all: Vec<usize> = (0..N_MOVE).collect();
+        // 6 faces with 3 turns each, plus 3*3 pairs for each of the 3 axes
+        assert_eq!(Metric::Half.axis_moves(&all).len(), 18 + 27);
+    }
+}
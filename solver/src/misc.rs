@@ -12,10 +12,10 @@ pub fn c_nk(n: u16, mut k: u16) -> u16 {
     let mut j = 1;
 
     while i != n - k {
-        s = s*i;
-        s = s/j;
-        i = i-1;
-        j = j+1;
+        s *= i;
+        s /= j;
+        i -= 1;
+        j += 1;
     }
 
     s
@@ -1,205 +1,327 @@
-use std::error::Error;
-use std::{fs::File, io::Write, path::Path};
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use std::{fs::{self, File}, io::{self, Write}, path::{Path, PathBuf}, process};
+#[cfg(feature = "std")]
 use std::io::{prelude::*, Read, SeekFrom};
 
 use strum::IntoEnumIterator;
-use bytemuck::{self, bytes_of};
 
-use crate::{common::{N_SLICE_SORTED, N_FLIP, N_TWIST, N_MOVE, Color}, cubie::{CubieCube, BASIC_MOVES}};
+use crate::{common::{N_SLICE_SORTED, N_FLIP, N_TWIST, N_MOVE, N_CORNERS, N_UD_EDGES, N_U_EDGES, Move}, cubie::CubieCube, packed::{PackedCube, MOVES}};
+#[cfg(feature = "std")]
+use crate::error::{Error, Result};
 
+// Table files are only read and written with std, generating them works anywhere
+#[cfg(feature = "std")]
 const BYTES_PER_U16: usize = 2;
 
+#[cfg(feature = "std")]
 const TWIST_SIZE: usize = N_TWIST*N_MOVE;
+#[cfg(feature = "std")]
 const TWIST_BYTES_SIZE: usize = TWIST_SIZE*BYTES_PER_U16;
 
+#[cfg(feature = "std")]
 const FLIP_SIZE: usize = N_FLIP*N_MOVE;
+#[cfg(feature = "std")]
 const FLIP_BYTES_SIZE: usize = FLIP_SIZE*BYTES_PER_U16;
 
+#[cfg(feature = "std")]
 const UD_SIZE: usize = N_SLICE_SORTED*N_MOVE;
+#[cfg(feature = "std")]
 const UD_BYTES_SIZE: usize = UD_SIZE*BYTES_PER_U16;
 
-/// Generate the twist move table
-fn gen_twist_move_table() -> Vec<u16> {
-    let mut twist_move = vec![0u16; TWIST_SIZE];
+#[cfg(feature = "std")]
+const CORNERS_SIZE: usize = N_CORNERS*N_MOVE;
+#[cfg(feature = "std")]
+const CORNERS_BYTES_SIZE: usize = CORNERS_SIZE*BYTES_PER_U16;
+
+#[cfg(feature = "std")]
+const UD_EDGES_SIZE: usize = N_UD_EDGES*N_MOVE;
+#[cfg(feature = "std")]
+const UD_EDGES_BYTES_SIZE: usize = UD_EDGES_SIZE*BYTES_PER_U16;
+
+#[cfg(feature = "std")]
+const U_EDGES_SIZE: usize = N_U_EDGES*N_MOVE;
+#[cfg(feature = "std")]
+const U_EDGES_BYTES_SIZE: usize = U_EDGES_SIZE*BYTES_PER_U16;
+
+/// Generic function to generate a move table of `n` coordinates. Each coordinate is set on a
+/// solved cube, which is packed so every move is a single multiply
+/// Only the moves `include` says are filled in, the rest are left as 0
+fn gen_move_table(n: usize, set: fn(&mut CubieCube, u16), multiply: fn(&mut PackedCube, &PackedCube), get: fn(&PackedCube) -> u16, include: fn(Move) -> bool) -> Vec<u16> {
+    let mut table = vec![0u16; n*N_MOVE];
     let mut a = CubieCube::new(None, None, None, None);
 
-    for i in 0..N_TWIST { // For each possible twist set
-        a.set_twist(i as u16); // Set the cube to that twist
-        for j in Color::iter() { // And for each face
-            for k in 0..3 { // For each move that can be done to that face
-                // Apply the move only to the corners
-                a.corner_multiply(&BASIC_MOVES[j as usize]);
-                // Save the twist result in the table
-                twist_move[N_MOVE*i + 3*j as usize + k] = a.get_twist();
-            }
-            a.corner_multiply(&BASIC_MOVES[j as usize]); // TODO: is simple?
+    for i in 0..n { // For each value of the coordinate
+        set(&mut a, i as u16); // Set the cube to it
+        let p = PackedCube::from(&a);
+        for m in Move::iter().filter(|&m| include(m)) { // And for each move
+            // Apply the move only to the pieces the coordinate looks at
+            let mut b = p;
+            multiply(&mut b, &MOVES[m as usize]);
+            // Save the result in the table
+            table[N_MOVE*i + m as usize] = get(&b);
         }
     }
 
-    twist_move
+    table
+}
+
+/// Generate the twist move table
+pub(crate) fn gen_twist_move_table() -> Vec<u16> {
+    gen_move_table(N_TWIST, CubieCube::set_twist, PackedCube::corner_multiply, PackedCube::get_twist, |_| true)
 }
 
 /// Generate edge flip move table
-fn gen_flip_move_table() -> Vec<u16> {
-    let mut flip_move = vec![0; FLIP_SIZE];
-    let mut a = CubieCube::new(None, None, None, None);
+pub(crate) fn gen_flip_move_table() -> Vec<u16> {
+    gen_move_table(N_FLIP, CubieCube::set_flip, PackedCube::edge_multiply, PackedCube::get_flip, |_| true)
+}
 
-    for i in 0..N_FLIP {
-        a.set_flip(i as u16);
-        for j in Color::iter() {
-            for k in 0..3 {
-                a.edge_multiply(&BASIC_MOVES[j as usize]);
-                flip_move[N_MOVE*i + 3*j as usize + k] = a.get_flip();
-            }
-            a.edge_multiply(&BASIC_MOVES[j as usize]);
-        }
-    }
+pub(crate) fn gen_ud_move_table() -> Vec<u16> {
+    gen_move_table(N_SLICE_SORTED, CubieCube::set_slice_sorted, PackedCube::edge_multiply, PackedCube::get_slice_sorted, |_| true)
+}
 
-    flip_move
+/// Generate the corner permutation move table
+pub(crate) fn gen_corners_move_table() -> Vec<u16> {
+    gen_move_table(N_CORNERS, CubieCube::set_corners, PackedCube::corner_multiply, PackedCube::get_corners, |_| true)
 }
 
-fn gen_ud_move_table() -> Vec<u16> {
-    let mut slice_sorted_move = vec![0; UD_SIZE];
-    let mut a = CubieCube::new(None, None, None, None);
+/// Generate the U/D edge permutation move table, only phase 2 moves are filled in
+/// since the other moves take the U/D edges into the slice
+pub(crate) fn gen_ud_edges_move_table() -> Vec<u16> {
+    gen_move_table(N_UD_EDGES, CubieCube::set_ud_edges, PackedCube::edge_multiply, PackedCube::get_ud_edges, Move::is_phase2)
+}
 
-    for i in 0..N_SLICE_SORTED {
-        a.set_slice_sorted(i as u16);
-        for j in Color::iter() {
-            for k in 0..3 {
-                a.edge_multiply(&BASIC_MOVES[j as usize]);
-                slice_sorted_move[N_MOVE*i + 3*j as usize + k] = a.get_slice_sorted();
-            }
-            a.edge_multiply(&BASIC_MOVES[j as usize]);
-        }
-    }
+/// Generate the U edges move table
+pub(crate) fn gen_u_edges_move_table() -> Vec<u16> {
+    gen_move_table(N_U_EDGES, CubieCube::set_u_edges, PackedCube::edge_multiply, PackedCube::get_u_edges, |_| true)
+}
+
+/// Generate the D edges move table
+pub(crate) fn gen_d_edges_move_table() -> Vec<u16> {
+    gen_move_table(N_U_EDGES, CubieCube::set_d_edges, PackedCube::edge_multiply, PackedCube::get_d_edges, |_| true)
+}
 
-    slice_sorted_move
+/// True if `table` is a move table for a coordinate with `n` values. The search indexes the
+/// table with its own entries, so a bad one would panic later
+pub(crate) fn valid_move_table(table: &[u16], n: usize) -> bool {
+    table.len() == n*N_MOVE && table.iter().all(|&c| (c as usize) < n)
 }
 
 /// Read from `f` into `buffer`, length of `BUFFER_SIZE` must be > length of `f`
-fn read_by_byte<const BUFFER_SIZE: usize>(f: &mut File, buffer: &mut Vec<u8>) {
+#[cfg(feature = "std")]
+fn read_by_byte<const BUFFER_SIZE: usize>(f: &mut File, buffer: &mut [u8]) -> io::Result<()> {
     for i in 0..(BUFFER_SIZE/2) {
         let b = BYTES_PER_U16*i; // Every 2 bytes
-        let _ = f.seek(SeekFrom::Start(b as u64));
+        f.seek(SeekFrom::Start(b as u64))?;
 
         let mut buf = [0u8; BYTES_PER_U16];
-        let _ = f.read_exact(&mut buf).unwrap();
+        f.read_exact(&mut buf)?;
 
         buffer[b..b+BYTES_PER_U16].copy_from_slice(&buf);
     }
+
+    Ok(())
 }
 
 /// Combine a byte array into an array of byte arrays (groups)
+#[cfg(feature = "std")]
 fn combine_byte_groups<const OUT_SIZE: usize>(buffer: Vec<u8>) -> Vec<[u8; BYTES_PER_U16]> {
     let mut bytes = vec![[0u8; BYTES_PER_U16]; OUT_SIZE];
 
-    for i in 0..OUT_SIZE {
+    for (i, group) in bytes.iter_mut().enumerate() {
         let j = BYTES_PER_U16*i;
-        for k in 0..BYTES_PER_U16 {
-            bytes[i][k] = buffer[j + k];
-        }
+        group.copy_from_slice(&buffer[j..j + BYTES_PER_U16]);
     };
  
     bytes
 }
 
+/// Write a table to `path`, creating the directory if needed
+/// Written to a temporary file first so that a reader never sees half a table
+#[cfg(feature = "std")]
+pub(crate) fn write_table(path: &Path, bytes: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let tmp = path.with_extension(format!("tmp{}_{}", process::id(), COUNTER.fetch_add(1, Ordering::Relaxed)));
+    let mut f = File::create(&tmp)?;
+    f.write_all(bytes)?;
+    fs::rename(&tmp, path)?;
+
+    Ok(())
+}
+
+/// Where a table is saved, `dir` is relative to `tables/`
+#[cfg(feature = "std")]
+pub(crate) fn table_path(dir: Option<&Path>, f_name: &str) -> PathBuf {
+    match dir {
+        Some(p) => Path::new("tables/").join(p).join(f_name),
+        None => Path::new("tables/").join(f_name),
+    }
+}
+
 /// Generic function to load/generate a move table
-fn load_move_table<const T_SIZE: usize, const T_BYTES_SIZE: usize>(f_name: &str, gen: impl Fn() -> Vec<u16>) -> Result<Vec<u16>, Box<dyn Error>> {
-    let dir = &format!("{}{}", "tables/", f_name);
-    let path = Path::new(dir);
-    match File::open(path) {
+/// A table that's there but the wrong size, or that points at coordinates past the end of it,
+/// is an error rather than being regenerated
+#[cfg(feature = "std")]
+fn load_move_table<const T_SIZE: usize, const T_BYTES_SIZE: usize>(dir: Option<&Path>, f_name: &str, gen: impl Fn() -> Vec<u16>) -> Result<Vec<u16>> {
+    let path = table_path(dir, f_name);
+    match File::open(&path) {
         Ok(mut f) => {
+            if f.metadata()?.len() != T_BYTES_SIZE as u64 {
+                return Err(Error::CorruptTable(path));
+            }
             let mut buffer = vec![0u8; T_BYTES_SIZE];
-            read_by_byte::<T_BYTES_SIZE>(&mut f, &mut buffer);
+            read_by_byte::<T_BYTES_SIZE>(&mut f, &mut buffer)?;
             let grouped_bytes: Vec<[u8; 2]> = combine_byte_groups::<T_SIZE>(buffer);
             let new_bytes: &[u16] = bytemuck::cast_slice(&grouped_bytes);
             let r = new_bytes.to_vec();
+            if !valid_move_table(&r, T_SIZE / N_MOVE) {
+                return Err(Error::CorruptTable(path));
+            }
             Ok(r)
         },
-        Err(_) => {
-            let mut f = File::create(path)?;
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
             let moves = gen();
-            let bytes: Vec<[u8; 2]> = bytemuck::cast_slice(&moves).to_vec();
-            for i in &bytes {
-                f.write_all(i).expect("Unable to write");
-            }
+            write_table(&path, bytemuck::cast_slice(&moves))?;
 
             Ok(moves)
         }
+        Err(e) => Err(e.into()),
     }
 }
 
 /// Load the twist move table, generating it if it doesn't exist
 /// Errors are just returned if generated
 /// `dir` can be optional path to the file 
-pub fn load_twist_move_table(dir: Option<&Path>) -> Result<Vec<u16>, Box<dyn Error>> {
-    match dir {
-        Some(p) => load_move_table::<TWIST_SIZE, TWIST_BYTES_SIZE>(p.join("move_twist").to_str().unwrap(), gen_twist_move_table),
-        None => load_move_table::<TWIST_SIZE, TWIST_BYTES_SIZE>("move_twist", gen_twist_move_table),
-    }
+#[cfg(feature = "std")]
+pub fn load_twist_move_table(dir: Option<&Path>) -> Result<Vec<u16>> {
+    load_move_table::<TWIST_SIZE, TWIST_BYTES_SIZE>(dir, "move_twist", gen_twist_move_table)
 }
 
-pub fn load_flip_move_table(dir: Option<&Path>) -> Result<Vec<u16>, Box<dyn Error>> {
-    match dir {
-        Some(p) => load_move_table::<FLIP_SIZE, FLIP_BYTES_SIZE>(p.join("move_flip").to_str().unwrap(), gen_flip_move_table),
-        None => load_move_table::<FLIP_SIZE, FLIP_BYTES_SIZE>("move_flip", gen_flip_move_table),
-    }
+#[cfg(feature = "std")]
+pub fn load_flip_move_table(dir: Option<&Path>) -> Result<Vec<u16>> {
+    load_move_table::<FLIP_SIZE, FLIP_BYTES_SIZE>(dir, "move_flip", gen_flip_move_table)
 }
 
-pub fn load_ud_move_table(dir: Option<&Path>) -> Result<Vec<u16>, Box<dyn Error>> {
-    match dir {
-        Some(p) => load_move_table::<UD_SIZE, UD_BYTES_SIZE>(p.join("move_slice_sorted").to_str().unwrap(), gen_ud_move_table),
-        None => load_move_table::<UD_SIZE, UD_BYTES_SIZE>("move_slice_sorted", gen_ud_move_table),
-    }
+#[cfg(feature = "std")]
+pub fn load_ud_move_table(dir: Option<&Path>) -> Result<Vec<u16>> {
+    load_move_table::<UD_SIZE, UD_BYTES_SIZE>(dir, "move_slice_sorted", gen_ud_move_table)
+}
+
+#[cfg(feature = "std")]
+pub fn load_corners_move_table(dir: Option<&Path>) -> Result<Vec<u16>> {
+    load_move_table::<CORNERS_SIZE, CORNERS_BYTES_SIZE>(dir, "move_corners", gen_corners_move_table)
+}
+
+#[cfg(feature = "std")]
+pub fn load_ud_edges_move_table(dir: Option<&Path>) -> Result<Vec<u16>> {
+    load_move_table::<UD_EDGES_SIZE, UD_EDGES_BYTES_SIZE>(dir, "move_ud_edges", gen_ud_edges_move_table)
+}
+
+#[cfg(feature = "std")]
+pub fn load_u_edges_move_table(dir: Option<&Path>) -> Result<Vec<u16>> {
+    load_move_table::<U_EDGES_SIZE, U_EDGES_BYTES_SIZE>(dir, "move_u_edges", gen_u_edges_move_table)
+}
+
+#[cfg(feature = "std")]
+pub fn load_d_edges_move_table(dir: Option<&Path>) -> Result<Vec<u16>> {
+    load_move_table::<U_EDGES_SIZE, U_EDGES_BYTES_SIZE>(dir, "move_d_edges", gen_d_edges_move_table)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    /// Return an empty array to ensure the test fails
-    fn zoinks<T, const S: usize>() -> Vec<T> where T: Default + Copy {
-        vec![T::default(); S]
-    }
-
-    #[test]
-    fn penis() {
-        //load_move_table::<UD_SIZE, UD_BYTES_SIZE>("move_slice_sorted", gen_ud_move_table);
-        let flips = load_ud_move_table(None).unwrap();
-        //let test = gen_ud_move_table();
-    }
+    use crate::tables::{digest, expected};
 
     /// Ensure that files are saved and loaded with the same data
     #[test]
     fn file_saves_and_loads() {
-        let _ = load_twist_move_table(None).unwrap();
-        assert_eq!(load_twist_move_table(None).unwrap(), gen_twist_move_table());
-        let _ = load_flip_move_table(None).unwrap();
-        assert_eq!(load_flip_move_table(None).unwrap(), gen_flip_move_table());
-        let _ = load_ud_move_table(None).unwrap();
-        assert_eq!(load_ud_move_table(None).unwrap(), gen_ud_move_table());
+        let dir = tempfile::tempdir().unwrap();
+        let dir = Some(dir.path());
+        assert!(!table_path(dir, "move_twist").exists());
+        assert_eq!(load_twist_move_table(dir).unwrap(), gen_twist_move_table());
+        assert!(table_path(dir, "move_twist").exists());
+        assert_eq!(load_twist_move_table(dir).unwrap(), gen_twist_move_table());
+
+        // Anything written by hand is read back as it is
+        let table: Vec<u16> = (0..FLIP_SIZE).map(|i| (i % N_FLIP) as u16).collect();
+        write_table(&table_path(dir, "move_flip"), bytemuck::cast_slice(&table)).unwrap();
+        assert_eq!(load_flip_move_table(dir).unwrap(), table);
     }
 
-    // Compare the twist data to a known good and ensure they match
     #[test]
-    fn twist_file_correct() {
-        let twists = gen_twist_move_table();
-        let good_twists = load_move_table::<TWIST_SIZE, TWIST_BYTES_SIZE>("known_good/move_twist", gen_twist_move_table).unwrap();
-        assert_eq!(twists, good_twists);
+    fn wrong_size_is_corrupt() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("move_twist"), [0u8; 10]).unwrap();
+        assert!(matches!(load_twist_move_table(Some(dir.path())), Err(Error::CorruptTable(_))));
+
+        // Right size but pointing past the end
+        let table = vec![N_FLIP as u16; FLIP_SIZE];
+        write_table(&dir.path().join("move_flip"), bytemuck::cast_slice(&table)).unwrap();
+        assert!(matches!(load_flip_move_table(Some(dir.path())), Err(Error::CorruptTable(_))));
+        fs::remove_file(dir.path().join("move_flip")).unwrap();
+
+        // Missing tables are generated
+        assert_eq!(load_flip_move_table(Some(dir.path())).unwrap(), gen_flip_move_table());
+    }
+
+    /// Every entry of `table` is the coordinate after doing the move to a cube with coordinate `i`,
+    /// and doing a move four times or then its inverse goes back to `i`
+    fn check_table(table: &[u16], n: usize, set: fn(&mut CubieCube, u16), get: fn(&CubieCube) -> u16, moves: &[Move]) {
+        assert_eq!(table.len(), n*N_MOVE);
+        for i in 0..n {
+            for &m in moves {
+                let mut cube = CubieCube::default();
+                set(&mut cube, i as u16);
+                cube.apply_move(m);
+                let j = table[N_MOVE*i + m as usize];
+                assert_eq!(j, get(&cube), "coordinate {} move {}", i, m);
+
+                let mut k = i;
+                for _ in 0..4 {
+                    k = table[N_MOVE*k + m as usize] as usize;
+                }
+                assert_eq!(k, i, "{} four times from {}", m, i);
+                assert_eq!(table[N_MOVE*j as usize + m.inverse() as usize] as usize, i, "{} then {} from {}", m, m.inverse(), i);
+            }
+        }
     }
 
-    // Compare the flip data to a known good and ensure they match
     #[test]
-    fn flip_file_correct() {
-        let flips = gen_flip_move_table();
-        let good_flips = load_move_table::<FLIP_SIZE, FLIP_BYTES_SIZE>("known_good/move_flip", gen_flip_move_table).unwrap();
-        assert_eq!(good_flips, flips);
+    fn move_tables_match_cubes() {
+        let all: Vec<Move> = Move::iter().collect();
+        let phase2: Vec<Move> = Move::iter().filter(|m| m.is_phase2()).collect();
+
+        check_table(&gen_twist_move_table(), N_TWIST, CubieCube::set_twist, CubieCube::get_twist, &all);
+        check_table(&gen_flip_move_table(), N_FLIP, CubieCube::set_flip, CubieCube::get_flip, &all);
+        check_table(&gen_ud_move_table(), N_SLICE_SORTED, CubieCube::set_slice_sorted, CubieCube::get_slice_sorted, &all);
+        check_table(&gen_corners_move_table(), N_CORNERS, CubieCube::set_corners, CubieCube::get_corners, &all);
+        check_table(&gen_ud_edges_move_table(), N_UD_EDGES, CubieCube::set_ud_edges, CubieCube::get_ud_edges, &phase2);
+        check_table(&gen_u_edges_move_table(), N_U_EDGES, CubieCube::set_u_edges, CubieCube::get_u_edges, &all);
+        check_table(&gen_d_edges_move_table(), N_U_EDGES, CubieCube::set_d_edges, CubieCube::get_d_edges, &all);
     }
 
+    /// Generated tables match the checked in digests
     #[test]
-    fn slice_sorted_file_correct() {
-        let slice_sorted = gen_ud_move_table();
-        let good_flips = load_move_table::<UD_SIZE, UD_BYTES_SIZE>("known_good/move_slice_sorted", gen_ud_move_table).unwrap();
-        assert_eq!(good_flips, slice_sorted);
+    fn digests_match() {
+        type Gen = fn() -> Vec<u16>;
+        let tables: [(&str, Gen); 7] = [
+            ("move_twist", gen_twist_move_table),
+            ("move_flip", gen_flip_move_table),
+            ("move_slice_sorted", gen_ud_move_table),
+            ("move_corners", gen_corners_move_table),
+            ("move_ud_edges", gen_ud_edges_move_table),
+            ("move_u_edges", gen_u_edges_move_table),
+            ("move_d_edges", gen_d_edges_move_table),
+        ];
+        for (name, gen) in tables {
+            let bytes: Vec<u8> = gen().iter().flat_map(|v| v.to_le_bytes()).collect();
+            assert_eq!(Some(digest(&bytes).as_str()), expected(name), "{}", name);
+        }
     }
 }
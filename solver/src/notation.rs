@@ -0,0 +1,397 @@
+use alloc::{string::{String, ToString}, vec::Vec};
+use core::fmt::Display;
+use core::str::FromStr;
+
+use crate::{
+    common::{Color, Move},
+    cubie::CubieCube,
+};
+
+/// Anything that can be written in standard notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    // Face turns
+    U,
+    R,
+    F,
+    D,
+    L,
+    B,
+    // Slices, M follows L, E follows D and S follows F
+    M,
+    E,
+    S,
+    // Two layers, also written in lower case (r = Rw)
+    Uw,
+    Rw,
+    Fw,
+    Dw,
+    Lw,
+    Bw,
+    // Whole cube rotations, x follows R, y follows U and z follows F
+    X,
+    Y,
+    Z,
+}
+
+/// A turn and how many clockwise quarter turns of it, 1..3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtMove {
+    pub turn: Turn,
+    pub power: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotationError {
+    pub token: String,
+}
+
+impl Display for NotationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid move `{}`", self.token)
+    }
+}
+
+impl core::error::Error for NotationError {}
+
+impl From<Move> for ExtMove {
+    fn from(m: Move) -> Self {
+        let turn = [Turn::U, Turn::R, Turn::F, Turn::D, Turn::L, Turn::B][m.face()];
+        ExtMove { turn, power: m.power() as u8 }
+    }
+}
+
+impl FromStr for ExtMove {
+    type Err = NotationError;
+
+    /// Parses things like `R`, `U2`, `F'`, `Rw2`, `r'`, `M`, `x2` and `y'`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || NotationError { token: s.to_string() };
+
+        let (turn, rest) = match s.get(..2) {
+            Some("Uw") => (Turn::Uw, &s[2..]),
+            Some("Rw") => (Turn::Rw, &s[2..]),
+            Some("Fw") => (Turn::Fw, &s[2..]),
+            Some("Dw") => (Turn::Dw, &s[2..]),
+            Some("Lw") => (Turn::Lw, &s[2..]),
+            Some("Bw") => (Turn::Bw, &s[2..]),
+            _ => {
+                let turn = match s.chars().next().ok_or_else(err)? {
+                    'U' => Turn::U,
+                    'R' => Turn::R,
+                    'F' => Turn::F,
+                    'D' => Turn::D,
+                    'L' => Turn::L,
+                    'B' => Turn::B,
+                    'M' => Turn::M,
+                    'E' => Turn::E,
+                    'S' => Turn::S,
+                    'u' => Turn::Uw,
+                    'r' => Turn::Rw,
+                    'f' => Turn::Fw,
+                    'd' => Turn::Dw,
+                    'l' => Turn::Lw,
+                    'b' => Turn::Bw,
+                    'x' => Turn::X,
+                    'y' => Turn::Y,
+                    'z' => Turn::Z,
+                    _ => return Err(err()),
+                };
+                (turn, &s[1..])
+            }
+        };
+
+        // Kociemba's U1/U3 style is accepted as well, and 2' is the same as 2
+        let power = match rest {
+            "" | "1" => 1,
+            "2" | "2'" => 2,
+            "'" | "3" => 3,
+            _ => return Err(err()),
+        };
+
+        Ok(ExtMove { turn, power })
+    }
+}
+
+impl Display for ExtMove {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let turn = match self.turn {
+            Turn::U => "U",
+            Turn::R => "R",
+            Turn::F => "F",
+            Turn::D => "D",
+            Turn::L => "L",
+            Turn::B => "B",
+            Turn::M => "M",
+            Turn::E => "E",
+            Turn::S => "S",
+            Turn::Uw => "Uw",
+            Turn::Rw => "Rw",
+            Turn::Fw => "Fw",
+            Turn::Dw => "Dw",
+            Turn::Lw => "Lw",
+            Turn::Bw => "Bw",
+            Turn::X => "x",
+            Turn::Y => "y",
+            Turn::Z => "z",
+        };
+        let suffix = ["", "2", "'"][self.power as usize - 1];
+        write!(f, "{}{}", turn, suffix)
+    }
+}
+
+impl FromStr for Move {
+    type Err = NotationError;
+
+    /// Face turns only, e.g. `R`, `U2` or `F'`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let m: ExtMove = s.parse()?;
+        let face = match m.turn {
+            Turn::U => 0,
+            Turn::R => 1,
+            Turn::F => 2,
+            Turn::D => 3,
+            Turn::L => 4,
+            Turn::B => 5,
+            _ => return Err(NotationError { token: s.to_string() }),
+        };
+        Ok(Move::from_index(3*face + m.power as usize - 1))
+    }
+}
+
+/// Whitespace separated moves in any notation
+pub fn parse_moves(s: &str) -> crate::error::Result<Vec<ExtMove>> {
+    Ok(s.split_whitespace().map(|t| t.parse()).collect::<Result<_, NotationError>>()?)
+}
+
+/// Whitespace separated face turns
+pub fn parse_face_moves(s: &str) -> crate::error::Result<Vec<Move>> {
+    Ok(s.split_whitespace().map(|t| t.parse()).collect::<Result<_, NotationError>>()?)
+}
+
+/// Which centre is at each face position, in U, R, F, D, L, B order. Starts out as
+/// `[U, R, F, D, L, B]` and changes with rotations and slice moves
+pub type Centers = [Color; 6];
+
+const SOLVED_CENTERS: Centers = [Color::U, Color::R, Color::F, Color::D, Color::L, Color::B];
+
+/// Rotate the centres a quarter turn, x follows R etc.
+fn rotate(centers: &mut Centers, axis: Turn) {
+    let c = *centers;
+    // Each cycle is (to, from), the centre at `from` moves to `to`
+    let cycle: [(usize, usize); 4] = match axis {
+        Turn::X => [(0, 2), (5, 0), (3, 5), (2, 3)], // F -> U -> B -> D -> F
+        Turn::Y => [(4, 2), (5, 4), (1, 5), (2, 1)], // F -> L -> B -> R -> F
+        Turn::Z => [(1, 0), (3, 1), (4, 3), (0, 4)], // U -> R -> D -> L -> U
+        _ => unreachable!(),
+    };
+    for (to, from) in cycle {
+        centers[to] = c[from];
+    }
+}
+
+/// Cube with its centres tracked, so slice moves, wide moves and rotations can be applied
+/// `cube` is always relative to the centres, so it can be passed straight to the solver
+#[derive(Clone, PartialEq)]
+pub struct OrientedCube {
+    pub cube: CubieCube,
+    pub centers: Centers,
+}
+
+impl Default for OrientedCube {
+    fn default() -> Self {
+        OrientedCube { cube: CubieCube::default(), centers: SOLVED_CENTERS }
+    }
+}
+
+impl OrientedCube {
+    /// Turn the face at position `face` (the way the cube is being held), which is the centre
+    /// that's there now
+    fn turn_face(&mut self, face: usize, power: u8, out: &mut Vec<Move>) {
+        let m = Move::from_index(3*self.centers[face] as usize + power as usize - 1);
+        self.cube.apply_move(m);
+        out.push(m);
+    }
+
+    fn rotate(&mut self, axis: Turn, power: u8) {
+        for _ in 0..power {
+            rotate(&mut self.centers, axis);
+        }
+    }
+
+    /// Apply any move, pushing the face turns it's made of onto `out`
+    fn apply_to(&mut self, m: ExtMove, out: &mut Vec<Move>) {
+        let p = m.power;
+        let inv = 4 - p; // The same number of turns the other way
+        match m.turn {
+            Turn::U => self.turn_face(0, p, out),
+            Turn::R => self.turn_face(1, p, out),
+            Turn::F => self.turn_face(2, p, out),
+            Turn::D => self.turn_face(3, p, out),
+            Turn::L => self.turn_face(4, p, out),
+            Turn::B => self.turn_face(5, p, out),
+            // M = R L' x', E = U D' y', S = F' B z
+            Turn::M => { self.turn_face(1, p, out); self.turn_face(4, inv, out); self.rotate(Turn::X, inv); }
+            Turn::E => { self.turn_face(0, p, out); self.turn_face(3, inv, out); self.rotate(Turn::Y, inv); }
+            Turn::S => { self.turn_face(2, inv, out); self.turn_face(5, p, out); self.rotate(Turn::Z, p); }
+            // Rw = L x, Uw = D y, Fw = B z, and the other way round for the other three
+            Turn::Rw => { self.turn_face(4, p, out); self.rotate(Turn::X, p); }
+            Turn::Uw => { self.turn_face(3, p, out); self.rotate(Turn::Y, p); }
+            Turn::Fw => { self.turn_face(5, p, out); self.rotate(Turn::Z, p); }
+            Turn::Lw => { self.turn_face(1, p, out); self.rotate(Turn::X, inv); }
+            Turn::Dw => { self.turn_face(0, p, out); self.rotate(Turn::Y, inv); }
+            Turn::Bw => { self.turn_face(2, p, out); self.rotate(Turn::Z, inv); }
+            Turn::X | Turn::Y | Turn::Z => self.rotate(m.turn, p),
+        }
+    }
+
+    pub fn apply(&mut self, m: ExtMove) {
+        self.apply_to(m, &mut Vec::new());
+    }
+
+    pub fn apply_moves(&mut self, moves: &[ExtMove]) {
+        for &m in moves {
+            self.apply(m);
+        }
+    }
+}
+
+/// Convert a sequence with slices, wide moves and rotations into face turns relative to the
+/// starting orientation, which is what the solver works with. Also returns where the centres
+/// end up, which is how the cube is being held at the end
+pub fn normalise(moves: &[ExtMove]) -> (Vec<Move>, Centers) {
+    let mut cube = OrientedCube::default();
+    let mut out = Vec::new();
+    for &m in moves {
+        cube.apply_to(m, &mut out);
+    }
+
+    (out, cube.centers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::Error, strategies};
+    use proptest::prelude::*;
+    use Move::*;
+
+    fn face_moves(s: &str) -> Vec<Move> {
+        parse_face_moves(s).unwrap()
+    }
+
+    #[test]
+    fn parse_and_print() {
+        let moves = parse_moves("R U2 F' Rw r2 M' E2 S x y' z2 U1 U3").unwrap();
+        let printed: Vec<String> = moves.iter().map(|m| m.to_string()).collect();
+        assert_eq!(printed.join(" "), "R U2 F' Rw Rw2 M' E2 S x y' z2 U U'");
+
+        assert_eq!(face_moves("R U2 F'"), vec![R1, U2, F3]);
+        assert!(parse_face_moves("R M").is_err());
+        assert!(matches!(parse_moves("R Q"), Err(Error::InvalidNotation(e)) if e.token == "Q"));
+    }
+
+    fn normalised(s: &str) -> OrientedCube {
+        let mut cube = OrientedCube::default();
+        cube.apply_moves(&parse_moves(s).unwrap());
+        cube
+    }
+
+    #[test]
+    fn rotations_only_move_centres() {
+        let (moves, centers) = normalise(&parse_moves("x y z2 y' x'").unwrap());
+        assert!(moves.is_empty());
+        assert_ne!(centers, SOLVED_CENTERS);
+
+        // x turns F to U, and turning about the axis y turns about gives z
+        assert_eq!(normalised("x").centers, [Color::F, Color::R, Color::D, Color::B, Color::L, Color::U]);
+        assert!(normalised("x y x'") == normalised("z"));
+        assert!(normalised("x x x x y y' z2 z2") == OrientedCube::default());
+    }
+
+    #[test]
+    fn slices_are_pairs_of_face_turns() {
+        assert_eq!(normalise(&parse_moves("M").unwrap()).0, vec![R1, L3]);
+        assert_eq!(normalise(&parse_moves("E'").unwrap()).0, vec![U3, D1]);
+        assert_eq!(normalise(&parse_moves("S2").unwrap()).0, vec![F2, B2]);
+
+        assert!(normalised("M") == normalised("R L' x'"));
+        assert!(normalised("E") == normalised("U D' y'"));
+        assert!(normalised("S") == normalised("F' B z"));
+    }
+
+    #[test]
+    fn wide_moves_are_face_and_slice() {
+        assert!(normalised("Rw") == normalised("R M'"));
+        assert!(normalised("Lw") == normalised("L M"));
+        assert!(normalised("Uw") == normalised("U E'"));
+        assert!(normalised("Dw") == normalised("D E"));
+        assert!(normalised("Fw") == normalised("F S"));
+        assert!(normalised("Bw") == normalised("B S'"));
+        assert!(normalised("r2 u' f") == normalised("Rw2 Uw' Fw"));
+    }
+
+    #[test]
+    fn every_move_undoes() {
+        for t in ["U", "R", "F", "D", "L", "B", "M", "E", "S", "Uw", "Rw", "Fw", "Dw", "Lw", "Bw", "x", "y", "z"] {
+            for p in ["", "2", "'"] {
+                let m: ExtMove = format!("{}{}", t, p).parse().unwrap();
+                let inv = ExtMove { turn: m.turn, power: 4 - m.power };
+                let mut cube = OrientedCube::default();
+                cube.apply(m);
+                cube.apply(inv);
+                assert!(cube == OrientedCube::default(), "{} {} isn't nothing", m, inv);
+            }
+        }
+    }
+
+    #[test]
+    fn moves_after_rotation_are_relabelled() {
+        // After x the F position holds the D centre
+        let (moves, _) = normalise(&parse_moves("x F").unwrap());
+        assert_eq!(moves, vec![D1]);
+        // r U r' is L x U x' L' which is L F L'
+        let (moves, centers) = normalise(&parse_moves("r U r'").unwrap());
+        assert_eq!(moves, face_moves("L F L'"));
+        assert_eq!(centers, SOLVED_CENTERS);
+    }
+
+    #[test]
+    fn m_slice_cycles_edges() {
+        let cube = normalised("M M M M");
+        assert!(cube.cube.is_solved() && cube.centers == SOLVED_CENTERS);
+
+        // (M' U)4 puts the centres back and done twice is nothing at all
+        let cube = normalised("M' U M' U M' U M' U");
+        assert!(cube.centers == SOLVED_CENTERS && !cube.cube.is_solved());
+        let cube = normalised(&["M' U M' U M' U M' U"; 2].join(" "));
+        assert!(cube == OrientedCube::default());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_print_and_parse(moves in strategies::moves(30)) {
+            let printed: Vec<String> = moves.iter().map(|m| ExtMove::from(*m).to_string()).collect();
+            prop_assert_eq!(parse_face_moves(&printed.join(" ")).unwrap(), moves);
+        }
+
+        #[test]
+        fn prop_any_string(s in "\\PC*") {
+            if let Ok(moves) = parse_moves(&s) {
+                normalise(&moves);
+            }
+            let _ = parse_face_moves(&s);
+        }
+
+        /// Close to real notation so it gets past the first letter more often
+        #[test]
+        fn prop_notation_like(s in "([URFDLBMESxyzurfdlb]w?[0-9']{0,2} ?){0,10}") {
+            if let Ok(moves) = parse_moves(&s) {
+                for m in &moves {
+                    prop_assert_eq!(m.to_string().parse::<ExtMove>().unwrap(), *m);
+                }
+                normalise(&moves);
+            }
+        }
+    }
+}
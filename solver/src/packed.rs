@@ -0,0 +1,346 @@
+use core::ops::Mul;
+
+use crate::{
+    common::{Corner, Edge, Move, N_MOVE},
+    cubie::{CubieCube, BASIC_MOVES},
+    simd,
+};
+
+// One byte per piece, the low nibble is which piece is there and the high nibble its orientation.
+// Byte i of `corners` is corner position i, byte i of `edges` is edge position i (the top 4 bytes
+// are always 0). Only real cubes can be packed, not the mirrored corners `CubieCube` allows
+const PIECE: u8 = 0x0f;
+const ORI_SHIFT: u8 = 4;
+
+/// `CubieCube` packed into two integers, quicker to copy, compare and multiply
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackedCube {
+    pub(crate) corners: u64,
+    pub(crate) edges: u128,
+}
+
+/// Every face turn as a packed cube, indexed by `Move`
+pub const MOVES: [PackedCube; N_MOVE] = {
+    let mut moves = [PackedCube::SOLVED; N_MOVE];
+    let mut m = 0;
+    while m < N_MOVE {
+        let face = PackedCube::from_cubie(&BASIC_MOVES[m / 3]);
+        let mut p = PackedCube::SOLVED;
+        let mut k = 0;
+        while k <= m % 3 {
+            p = p.product(&face);
+            k += 1;
+        }
+        moves[m] = p;
+        m += 1;
+    }
+    moves
+};
+
+impl PackedCube {
+    pub const SOLVED: PackedCube = PackedCube {
+        corners: u64::from_le_bytes([0, 1, 2, 3, 4, 5, 6, 7]),
+        edges: u128::from_le_bytes([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 0, 0, 0, 0]),
+    };
+
+    pub const fn from_cubie(cube: &CubieCube) -> PackedCube {
+        let mut corners = [0u8; 8];
+        let mut i = 0;
+        while i < 8 {
+            corners[i] = cube.cp[i] as u8 | (cube.co[i] as u8) << ORI_SHIFT;
+            i += 1;
+        }
+        let mut edges = [0u8; 16];
+        let mut i = 0;
+        while i < 12 {
+            edges[i] = cube.ep[i] as u8 | (cube.eo[i] as u8) << ORI_SHIFT;
+            i += 1;
+        }
+
+        PackedCube { corners: u64::from_le_bytes(corners), edges: u128::from_le_bytes(edges) }
+    }
+
+    pub fn to_cubie(&self) -> CubieCube {
+        let mut cube = CubieCube::default();
+        for (i, b) in self.corner_bytes().into_iter().enumerate() {
+            cube.cp[i] = CORNERS[(b & PIECE) as usize];
+            cube.co[i] = (b >> ORI_SHIFT) as i8;
+        }
+        for (i, &b) in self.edge_bytes().iter().take(12).enumerate() {
+            cube.ep[i] = EDGES[(b & PIECE) as usize];
+            cube.eo[i] = (b >> ORI_SHIFT) as i8;
+        }
+        cube
+    }
+
+    pub(crate) const fn corner_bytes(&self) -> [u8; 8] {
+        self.corners.to_le_bytes()
+    }
+
+    pub(crate) const fn edge_bytes(&self) -> [u8; 16] {
+        self.edges.to_le_bytes()
+    }
+
+    /// `self * b`, usable in consts
+    const fn product(&self, b: &Self) -> PackedCube {
+        PackedCube {
+            corners: corner_product(self.corners, b.corners),
+            edges: edge_product(self.edges, b.edges),
+        }
+    }
+
+    /// Same as `CubieCube::corner_multiply` for real cubes, with byte shuffles where the CPU has them
+    pub fn corner_multiply(&mut self, b: &Self) {
+        self.corners = simd::corner_product(self.corners, b.corners);
+    }
+
+    pub fn edge_multiply(&mut self, b: &Self) {
+        self.edges = simd::edge_product(self.edges, b.edges);
+    }
+
+    pub fn multiply(&mut self, b: &Self) {
+        self.corner_multiply(b);
+        self.edge_multiply(b);
+    }
+
+    /// A face turn is a single multiply, unlike `CubieCube` which does each quarter turn
+    pub fn apply_move(&mut self, m: Move) {
+        self.multiply(&MOVES[m as usize]);
+    }
+
+    pub fn apply_moves(&mut self, moves: &[Move]) {
+        for &m in moves {
+            self.apply_move(m);
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == PackedCube::SOLVED
+    }
+
+    // The coordinates are the same as `CubieCube`'s, see there
+
+    pub fn get_twist(&self) -> u16 {
+        let c = self.corner_bytes();
+        c[..7].iter().fold(0, |total, &b| 3*total + (b >> ORI_SHIFT) as u16)
+    }
+
+    pub fn get_flip(&self) -> u16 {
+        let e = self.edge_bytes();
+        e[..11].iter().fold(0, |total, &b| 2*total + (b >> ORI_SHIFT) as u16)
+    }
+
+    pub fn get_slice_sorted(&self) -> u16 {
+        perm_4(&self.edge_bytes(), Edge::FR as u8, 0)
+    }
+
+    // `CubieCube` rotates the U and D edges round to the end first, same as starting 8 along
+
+    pub fn get_u_edges(&self) -> u16 {
+        perm_4(&self.edge_bytes(), Edge::UR as u8, 8)
+    }
+
+    pub fn get_d_edges(&self) -> u16 {
+        perm_4(&self.edge_bytes(), Edge::DR as u8, 8)
+    }
+
+    pub fn get_corners(&self) -> u16 {
+        perm_8(self.corner_bytes())
+    }
+
+    pub fn get_ud_edges(&self) -> u16 {
+        let e = self.edge_bytes();
+        perm_8([e[0], e[1], e[2], e[3], e[4], e[5], e[6], e[7]])
+    }
+}
+
+const CORNERS: [Corner; 8] = [Corner::UFR, Corner::UFL, Corner::UBL, Corner::UBR, Corner::DFR, Corner::DFL, Corner::DBL, Corner::DBR];
+const EDGES: [Edge; 12] = [Edge::UR, Edge::UF, Edge::UL, Edge::UB, Edge::DR, Edge::DF, Edge::DL, Edge::DB, Edge::FR, Edge::FL, Edge::BL, Edge::BR];
+
+// Sum of two corner orientations mod 3, a lookup rather than a branch
+const MOD_3: [u8; 6] = [0, 1, 2, 0, 1, 2];
+
+// (A*B)(x).c = A(B(x).c).c, (A*B)(x).o = A(B(x).c).o + B(x).o, as in `CubieCube`
+
+pub(crate) const fn corner_product(a: u64, b: u64) -> u64 {
+    let a = a.to_le_bytes();
+    let b = b.to_le_bytes();
+    let mut c = [0u8; 8];
+    let mut i = 0;
+    while i < 8 {
+        let x = a[(b[i] & PIECE) as usize];
+        let o = MOD_3[((x >> ORI_SHIFT) + (b[i] >> ORI_SHIFT)) as usize];
+        c[i] = (x & PIECE) | o << ORI_SHIFT;
+        i += 1;
+    }
+    u64::from_le_bytes(c)
+}
+
+pub(crate) const fn edge_product(a: u128, b: u128) -> u128 {
+    let a = a.to_le_bytes();
+    let b = b.to_le_bytes();
+    let mut c = [0u8; 16];
+    let mut i = 0;
+    while i < 12 {
+        // Orientation is one bit so adding mod 2 is xor
+        c[i] = a[(b[i] & PIECE) as usize] ^ (b[i] & !PIECE);
+        i += 1;
+    }
+    u128::from_le_bytes(c)
+}
+
+// n choose k for k up to 4, `c_nk` is too slow to call for every edge
+const C_NK: [[u16; 5]; 12] = {
+    let mut c = [[0; 5]; 12];
+    let mut n = 0;
+    while n < 12 {
+        c[n][0] = 1;
+        let mut k = 1;
+        while k < 5 && k <= n {
+            c[n][k] = if n == 0 { 0 } else { c[n - 1][k - 1] + c[n - 1][k] };
+            k += 1;
+        }
+        n += 1;
+    }
+    c
+};
+
+/// Where 4 edges `first..first + 4` are and what order they're in, reading the edges from
+/// position `start` round to `start - 1`
+fn perm_4(edges: &[u8; 16], first: u8, start: usize) -> u16 {
+    let mut a = 0;
+    let mut x = 0;
+    let mut edge_4 = [0u8; 4];
+
+    for j in (0..12).rev() {
+        let e = (edges[(j + start) % 12] & PIECE).wrapping_sub(first);
+        if e < 4 {
+            a += C_NK[11 - j][x + 1];
+            edge_4[3 - x] = e;
+            x += 1;
+        }
+    }
+
+    24*a + rank(u64::from_le_bytes([edge_4[0], edge_4[1], edge_4[2], edge_4[3], 0, 0, 0, 0]), 4)
+}
+
+/// Permutation of 8 pieces
+fn perm_8(bytes: [u8; 8]) -> u16 {
+    rank(u64::from_le_bytes(bytes.map(|b| b & PIECE)), 8)
+}
+
+/// The rotation counting coordinate of `CubieCube::get_corners` for the first `n` bytes of
+/// `perm`, all the rotations needed for each piece are done at once with shifts
+fn rank(mut perm: u64, n: usize) -> u16 {
+    let mut b = 0;
+
+    for j in (1..n).rev() {
+        // Anything past the first j + 1 bytes isn't looked at again
+        let width = 8*(j + 1) as u32;
+        if width < 64 {
+            perm &= (1 << width) - 1;
+        }
+
+        let p = perm.to_le_bytes()[..=j].iter().position(|&x| x as usize == j).unwrap_or(j);
+        let k = (p + 1) % (j + 1);
+        if k != 0 {
+            // Rotate left by k
+            perm = perm >> (8*k) | perm << (width - 8*k as u32);
+        }
+        b = (j as u16 + 1)*b + k as u16;
+    }
+
+    b
+}
+
+impl From<&CubieCube> for PackedCube {
+    fn from(cube: &CubieCube) -> Self {
+        PackedCube::from_cubie(cube)
+    }
+}
+
+impl From<&PackedCube> for CubieCube {
+    fn from(cube: &PackedCube) -> Self {
+        cube.to_cubie()
+    }
+}
+
+impl Default for PackedCube {
+    fn default() -> Self {
+        PackedCube::SOLVED
+    }
+}
+
+impl Mul for PackedCube {
+    type Output = PackedCube;
+
+    fn mul(mut self, rhs: Self) -> Self::Output {
+        self.multiply(&rhs);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies;
+    use proptest::prelude::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn solved_and_moves() {
+        assert_eq!(PackedCube::from(&CubieCube::default()), PackedCube::SOLVED);
+        assert!(PackedCube::SOLVED.to_cubie().is_solved());
+        for m in Move::iter() {
+            let mut cube = CubieCube::default();
+            cube.apply_move(m);
+            assert_eq!(MOVES[m as usize], PackedCube::from(&cube), "{}", m);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_round_trip(cube in strategies::cube()) {
+            prop_assert_eq!(PackedCube::from(&cube).to_cubie(), cube);
+        }
+
+        #[test]
+        fn prop_multiply(a in strategies::cube(), b in strategies::cube()) {
+            let packed = PackedCube::from(&a) * PackedCube::from(&b);
+            prop_assert_eq!(packed.to_cubie(), a * b);
+        }
+
+        #[test]
+        fn prop_moves(a in strategies::cube(), moves in strategies::moves(20)) {
+            let mut packed = PackedCube::from(&a);
+            let mut cube = a;
+            packed.apply_moves(&moves);
+            cube.apply_moves(&moves);
+            prop_assert_eq!(packed.to_cubie(), cube);
+        }
+
+        #[test]
+        fn prop_coordinates(cube in strategies::cube()) {
+            let packed = PackedCube::from(&cube);
+            prop_assert_eq!(packed.get_twist(), cube.get_twist());
+            prop_assert_eq!(packed.get_flip(), cube.get_flip());
+            prop_assert_eq!(packed.get_slice_sorted(), cube.get_slice_sorted());
+            prop_assert_eq!(packed.get_u_edges(), cube.get_u_edges());
+            prop_assert_eq!(packed.get_d_edges(), cube.get_d_edges());
+            prop_assert_eq!(packed.get_corners(), cube.get_corners());
+        }
+
+        /// Only defined in phase 2
+        #[test]
+        fn prop_ud_edges(corners in 0..40320u16, ud_edges in 0..40320u16, slice_sorted in 0..24u16) {
+            let mut cube = CubieCube::default();
+            cube.set_slice_sorted(slice_sorted);
+            cube.set_ud_edges(ud_edges);
+            cube.set_corners(corners);
+            let packed = PackedCube::from(&cube);
+            prop_assert_eq!(packed.get_ud_edges(), ud_edges);
+            prop_assert_eq!(packed.get_corners(), corners);
+            prop_assert_eq!(packed.get_slice_sorted(), slice_sorted);
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use alloc::{vec, vec::Vec};
+use arrayvec::ArrayVec;
+use strum::IntoEnumIterator;
+
+use crate::{
+    common::{Corner, Edge, Move, N_MOVE},
+    cubie::CubieCube,
+    error::Result,
+    search::Solution,
+};
+
+// Pieces are split into groups of at most this many for the pruning tables,
+// 4 pieces is (8*3)^4 or (12*2)^4 = 331776 entries
+const GROUP_SIZE: usize = 4;
+const EMPTY: u8 = 0xFF;
+
+/// Which parts of the cube have to be solved, indexed by piece like `CubieCube`'s arrays
+/// `cp`/`ep` mean the piece has to be home, `co`/`eo` mean it has to be oriented wherever it is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Mask {
+    pub cp: [bool; 8],
+    pub co: [bool; 8],
+    pub ep: [bool; 12],
+    pub eo: [bool; 12],
+}
+
+impl Mask {
+    /// DF, DR, DB and DL edges
+    pub fn cross() -> Mask {
+        let mut mask = Mask::default();
+        for e in [Edge::DR, Edge::DF, Edge::DL, Edge::DB] {
+            mask.ep[e as usize] = true;
+            mask.eo[e as usize] = true;
+        }
+        mask
+    }
+
+    /// Cross plus the D corners and the slice edges
+    pub fn f2l() -> Mask {
+        let mut mask = Mask::cross();
+        for e in [Edge::FR, Edge::FL, Edge::BL, Edge::BR] {
+            mask.ep[e as usize] = true;
+            mask.eo[e as usize] = true;
+        }
+        for c in [Corner::DFR, Corner::DFL, Corner::DBL, Corner::DBR] {
+            mask.cp[c as usize] = true;
+            mask.co[c as usize] = true;
+        }
+        mask
+    }
+
+    /// F2L with the last layer oriented
+    pub fn oll() -> Mask {
+        let mut mask = Mask::f2l();
+        mask.co = [true; 8];
+        mask.eo = [true; 12];
+        mask
+    }
+
+    /// True if every masked part of `cube` is solved
+    pub fn is_solved(&self, cube: &CubieCube) -> bool {
+        let corners_ok = (0..8).all(|p| {
+            let c = cube.cp[p] as usize;
+            (!self.cp[c] || c == p) && (!self.co[c] || cube.co[p] == 0)
+        });
+        let edges_ok = (0..12).all(|p| {
+            let e = cube.ep[p] as usize;
+            (!self.ep[e] || e == p) && (!self.eo[e] || cube.eo[p] == 0)
+        });
+
+        corners_ok && edges_ok
+    }
+}
+
+/// Where each position goes for a move and how much the orientation changes
+type MoveMap = Vec<[(usize, u8); N_MOVE]>;
+
+/// Some pieces of one kind tracked together with a pruning table over their positions
+/// and orientations
+struct Group {
+    pieces: Vec<usize>,
+    corners: bool,
+    table: Vec<u8>,
+}
+
+impl Group {
+    fn n_ori(&self) -> usize {
+        if self.corners { 3 } else { 2 }
+    }
+
+    fn n_pos(&self) -> usize {
+        if self.corners { 8 } else { 12 }
+    }
+
+    /// Mixed radix number, one (position, orientation) digit per piece
+    fn index(&self, state: &[(usize, u8)]) -> usize {
+        let base = self.n_pos()*self.n_ori();
+        state.iter().rev().fold(0, |idx, &(p, o)| base*idx + self.n_ori()*p + o as usize)
+    }
+
+    fn state(&self, mut idx: usize) -> ArrayVec<(usize, u8), GROUP_SIZE> {
+        let base = self.n_pos()*self.n_ori();
+        (0..self.pieces.len()).map(|_| {
+            let digit = idx % base;
+            idx /= base;
+            (digit / self.n_ori(), (digit % self.n_ori()) as u8)
+        }).collect()
+    }
+
+    /// Breadth first search outwards from every state that satisfies the mask
+    fn gen_table(&mut self, mask: &Mask, move_map: &MoveMap) {
+        let size = (self.n_pos()*self.n_ori()).pow(self.pieces.len() as u32);
+        let mut table = vec![EMPTY; size];
+
+        for (i, entry) in table.iter_mut().enumerate() {
+            let goal = self.state(i).iter().zip(&self.pieces).all(|(&(p, o), &piece)| {
+                let (perm, orie) = if self.corners { (mask.cp[piece], mask.co[piece]) } else { (mask.ep[piece], mask.eo[piece]) };
+                (!perm || p == piece) && (!orie || o == 0)
+            });
+            if goal {
+                *entry = 0;
+            }
+        }
+
+        let n_ori = self.n_ori() as u8;
+        let mut depth = 0;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for i in 0..size {
+                if table[i] != depth {
+                    continue;
+                }
+                let state = self.state(i);
+                for m in Move::iter() {
+                    let next: ArrayVec<(usize, u8), GROUP_SIZE> = state.iter().map(|&(p, o)| {
+                        let (q, d) = move_map[p][m as usize];
+                        (q, (o + d) % n_ori)
+                    }).collect();
+                    let j = self.index(&next);
+                    if table[j] == EMPTY {
+                        table[j] = depth + 1;
+                        changed = true;
+                    }
+                }
+            }
+            depth += 1;
+        }
+
+        self.table = table;
+    }
+}
+
+/// IDA* search for a cube where only part of it has to be solved, e.g. the cross
+pub struct PartialSolver {
+    mask: Mask,
+    corner_map: MoveMap,
+    edge_map: MoveMap,
+    groups: Vec<Group>,
+    // Pieces tracked by the search, corners then edges
+    corners: Vec<usize>,
+    edges: Vec<usize>,
+}
+
+impl PartialSolver {
+    /// Generate the pruning tables for `mask`, they're small enough not to be saved
+    pub fn new(mask: Mask) -> Self {
+        let mut corner_map = vec![[(0, 0); N_MOVE]; 8];
+        let mut edge_map = vec![[(0, 0); N_MOVE]; 12];
+        for m in Move::iter() {
+            let mut mc = CubieCube::default();
+            mc.apply_move(m);
+            // Multiplying by the move puts the piece at position cp[q] into q
+            for q in 0..8 {
+                corner_map[mc.cp[q] as usize][m as usize] = (q, mc.co[q] as u8);
+            }
+            for q in 0..12 {
+                edge_map[mc.ep[q] as usize][m as usize] = (q, mc.eo[q] as u8);
+            }
+        }
+
+        let corners: Vec<usize> = (0..8).filter(|&c| mask.cp[c] || mask.co[c]).collect();
+        let edges: Vec<usize> = (0..12).filter(|&e| mask.ep[e] || mask.eo[e]).collect();
+
+        let mut groups = Vec::new();
+        for chunk in corners.chunks(GROUP_SIZE) {
+            let mut group = Group { pieces: chunk.to_vec(), corners: true, table: Vec::new() };
+            group.gen_table(&mask, &corner_map);
+            groups.push(group);
+        }
+        for chunk in edges.chunks(GROUP_SIZE) {
+            let mut group = Group { pieces: chunk.to_vec(), corners: false, table: Vec::new() };
+            group.gen_table(&mask, &edge_map);
+            groups.push(group);
+        }
+
+        PartialSolver { mask, corner_map, edge_map, groups, corners, edges }
+    }
+
+    pub fn mask(&self) -> &Mask {
+        &self.mask
+    }
+
+    /// Shortest solution of at most `max_length` moves for the masked part of `cube`
+    pub fn solve(&self, cube: &CubieCube, max_length: usize) -> Result<Option<Solution>> {
+        Ok(self.solve_all(cube, max_length, 1)?.pop())
+    }
+
+    /// Up to `limit` canonical solutions, shortest first. Every solution of one length is
+    /// found before any longer ones
+    pub fn solve_all(&self, cube: &CubieCube, max_length: usize, limit: usize) -> Result<Vec<Solution>> {
+        // Every piece is somewhere once verified
+        cube.verify()?;
+
+        // Position and orientation of each tracked piece
+        let mut corners = Vec::new();
+        for &c in &self.corners {
+            let p = cube.cp.iter().position(|&x| x as usize == c).unwrap();
+            corners.push((p, cube.co[p] as u8 % 3));
+        }
+        let mut edges = Vec::new();
+        for &e in &self.edges {
+            let p = cube.ep.iter().position(|&x| x as usize == e).unwrap();
+            edges.push((p, cube.eo[p] as u8));
+        }
+
+        let mut search = PartialSearch { solver: self, limit, path: Vec::new(), solutions: Vec::new() };
+        for depth in self.dist(&corners, &edges)..=max_length {
+            search.search(&corners, &edges, depth);
+            if search.solutions.len() >= limit {
+                break;
+            }
+        }
+
+        Ok(search.solutions)
+    }
+
+    /// Largest of the group pruning tables
+    fn dist(&self, corners: &[(usize, u8)], edges: &[(usize, u8)]) -> usize {
+        let mut c = 0;
+        let mut e = 0;
+        let mut h = 0;
+        for group in &self.groups {
+            let n = group.pieces.len();
+            let idx = if group.corners {
+                c += n;
+                group.index(&corners[c - n..c])
+            } else {
+                e += n;
+                group.index(&edges[e - n..e])
+            };
+            h = h.max(group.table[idx]);
+        }
+        h as usize
+    }
+}
+
+struct PartialSearch<'a> {
+    solver: &'a PartialSolver,
+    limit: usize,
+    path: Vec<Move>,
+    solutions: Vec<Solution>,
+}
+
+impl PartialSearch<'_> {
+    /// Depth first search for sequences of exactly `togo` more moves
+    fn search(&mut self, corners: &[(usize, u8)], edges: &[(usize, u8)], togo: usize) {
+        if self.solutions.len() >= self.limit {
+            return;
+        }
+        if togo == 0 {
+            // Only reached when all the pruning tables are 0
+            self.solutions.push(Solution { moves: self.path.clone(), cost: self.path.len() });
+            return;
+        }
+
+        let s = self.solver;
+        for m in Move::iter() {
+            if let Some(l) = self.path.last() {
+                // Same face, or the opposite face in the wrong order
+                if l.face() == m.face() || (l.face() % 3 == m.face() % 3 && m.face() < l.face()) {
+                    continue;
+                }
+            }
+
+            let corners1: Vec<(usize, u8)> = corners.iter().map(|&(p, o)| {
+                let (q, d) = s.corner_map[p][m as usize];
+                (q, (o + d) % 3)
+            }).collect();
+            let edges1: Vec<(usize, u8)> = edges.iter().map(|&(p, o)| {
+                let (q, d) = s.edge_map[p][m as usize];
+                (q, (o + d) % 2)
+            }).collect();
+            if s.dist(&corners1, &edges1) >= togo {
+                continue;
+            }
+
+            self.path.push(m);
+            self.search(&corners1, &edges1, togo - 1);
+            self.path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Move::*;
+
+    fn scrambled(moves: &[Move]) -> CubieCube {
+        let mut cube = CubieCube::default();
+        cube.apply_moves(moves);
+        cube
+    }
+
+    #[test]
+    fn cross() {
+        let cube = scrambled(&[R1, U1, F3, L2, D1, B1, R2, U3, F1, D2, L1, B3]);
+        let solver = PartialSolver::new(Mask::cross());
+        let solution = solver.solve(&cube, 8).unwrap().unwrap();
+
+        let mut c = cube.clone();
+        c.apply_moves(&solution.moves);
+        assert!(Mask::cross().is_solved(&c));
+        assert!(!c.is_solved());
+    }
+
+    #[test]
+    fn cross_shortest_first() {
+        let cube = scrambled(&[F2, R1]);
+        let solutions = PartialSolver::new(Mask::cross()).solve_all(&cube, 4, 20).unwrap();
+        assert_eq!(solutions[0].moves, vec![R3, F2]);
+        assert!(solutions.windows(2).all(|w| w[0].len() <= w[1].len()));
+    }
+
+    #[test]
+    fn orient_last_layer() {
+        // Sune leaves three corners twisted
+        let cube = scrambled(&[R1, U1, R3, U1, R1, U2, R3]);
+        assert!(Mask::f2l().is_solved(&cube));
+        assert!(!Mask::oll().is_solved(&cube));
+
+        let solution = PartialSolver::new(Mask::oll()).solve(&cube, 7).unwrap().unwrap();
+        let mut c = cube.clone();
+        c.apply_moves(&solution.moves);
+        assert!(Mask::oll().is_solved(&c));
+        assert!(solution.len() <= 7);
+    }
+
+    #[test]
+    fn invalid_cube() {
+        use crate::{cubie::CubieError, error::Error};
+        let solver = PartialSolver::new(Mask::cross());
+        let missing = CubieCube::new(None, None, Some([Edge::UR; 12]), None);
+        assert!(matches!(solver.solve_all(&missing, 8, 1), Err(Error::InvalidCube(CubieError::EdgePermutation))));
+        let twisted = CubieCube::new(None, Some([1, 0, 0, 0, 0, 0, 0, 0]), None, None);
+        assert!(matches!(solver.solve(&twisted, 8), Err(Error::InvalidCube(CubieError::Twist))));
+    }
+}
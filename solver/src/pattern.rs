@@ -0,0 +1,56 @@
+use alloc::{vec, vec::Vec};
+use strum_macros::EnumIter;
+
+use crate::{common::Move, cubie::CubieCube};
+
+/// Well known patterns that can be used as a target for `Solver::solve_to`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum Pattern {
+    /// Every edge flipped in place
+    Superflip,
+    /// Each face alternates between its own colour and the opposite one
+    Checkerboard,
+    /// A 2x2x2 cube inside a 3x3x3 cube in the UFR corner
+    CubeInCube,
+    /// Each centre is surrounded by the opposite colour
+    SixSpots,
+}
+
+impl Pattern {
+    /// Face turns that make the pattern from a solved cube
+    pub fn moves(self) -> Vec<Move> {
+        use Move::*;
+        match self {
+            Pattern::Superflip => vec![
+                U1, R2, F1, B1, R1, B2, R1, U2, L1, B2, R1, U3, D3, R2, F1, R3, L1, B2, U2, F2,
+            ],
+            Pattern::Checkerboard => vec![R2, L2, U2, D2, F2, B2],
+            Pattern::CubeInCube => vec![F1, L1, F1, U3, R1, U1, F2, L2, U3, L3, B1, D3, B3, L2, U1],
+            Pattern::SixSpots => vec![U1, D3, R1, L3, F1, B3, U1, D3],
+        }
+    }
+
+    pub fn cube(self) -> CubieCube {
+        let mut cube = CubieCube::default();
+        cube.apply_moves(&self.moves());
+        cube
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn superflip_flips_every_edge() {
+        let cube = CubieCube::new(None, None, None, Some([1; 12]));
+        assert!(Pattern::Superflip.cube() == cube);
+    }
+
+    #[test]
+    fn checkerboard_is_its_own_inverse() {
+        let cube = Pattern::Checkerboard.cube();
+        assert!(!cube.is_solved());
+        assert!((cube.clone() * cube).is_solved());
+    }
+}
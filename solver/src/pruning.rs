@@ -0,0 +1,407 @@
+use alloc::{vec, vec::Vec};
+use core::fmt::Display;
+#[cfg(feature = "std")]
+use std::{fs, io, path::{Path, PathBuf}};
+
+use crate::{
+    common::{Move, N_FLIP, N_MOVE, N_PERM_4, N_SLICE, N_SLICE_SORTED, N_TWIST, N_CORNERS, N_U_EDGES, N_UD_EDGES},
+    metric::{AxisMove, Metric},
+    symmetry::{Symmetries, N_FLIPSLICE_CLASS},
+};
+#[cfg(feature = "std")]
+use crate::{
+    error::{Error, Result},
+    moves::{table_path, write_table},
+};
+
+// Pruning tables store the number of moves needed to solve a pair of coordinates, which is
+// a lower bound for the whole cube. One byte per entry, 0xFF means not reached yet
+pub(crate) const EMPTY: u8 = 0xFF;
+
+pub(crate) const TWIST_SLICE_SIZE: usize = N_TWIST*N_SLICE;
+pub(crate) const FLIP_SLICE_SIZE: usize = N_FLIP*N_SLICE;
+pub(crate) const CORNERS_SLICE_SIZE: usize = N_CORNERS*N_PERM_4;
+pub(crate) const UD_EDGES_SLICE_SIZE: usize = N_UD_EDGES*N_PERM_4;
+
+// The symmetry reduced phase 1 table is big enough to pack two entries a byte, low nibble
+// first. Phase 1 never takes more than 12 moves so 0xF is free for not reached
+const EMPTY_NIBBLE: u8 = 0xF;
+pub(crate) const PHASE1_SYM_SIZE: usize = (N_FLIPSLICE_CLASS*N_TWIST).div_ceil(2);
+
+/// Which pruning tables a solver uses, from the most memory and fastest to the least and
+/// slowest. See `for_budget` to pick one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TableSet {
+    /// One table of twist, flip and slice together for phase 1, shrunk 16 times by symmetry.
+    /// Exact phase 1 distances, only for all 18 moves in the half turn metric
+    Symmetric,
+    /// Twist and flip each paired with the slice for phase 1, the default
+    TwistFlip,
+    /// `TwistFlip` without the phase 2 pruning tables, phase 2 only prunes on the corners and
+    /// the U/D edges on their own. Pruning nothing at all would never finish phase 2
+    Small,
+}
+
+impl TableSet {
+    /// Biggest first
+    pub const ALL: [TableSet; 3] = [TableSet::Symmetric, TableSet::TwistFlip, TableSet::Small];
+
+    /// False if the tables can't be used with `moves` in `metric`, only `Symmetric` is limited
+    pub fn supports(self, moves: &[Move], metric: Metric) -> bool {
+        match self {
+            TableSet::Symmetric => metric == Metric::Half && (0..N_MOVE).all(|m| moves.iter().any(|&n| n as usize == m)),
+            TableSet::TwistFlip | TableSet::Small => true,
+        }
+    }
+
+    /// Bytes of move and pruning tables a solver using the set for `moves` holds
+    pub fn bytes(self, moves: &[Move]) -> usize {
+        let mut bytes = 2*N_MOVE*(N_TWIST + N_FLIP + N_SLICE_SORTED + N_CORNERS + N_UD_EDGES);
+        if (0..N_MOVE).any(|m| !moves.iter().any(|&n| n as usize == m)) {
+            // Which coordinates can be reached, the U and D edge move tables are dropped once
+            // that's worked out
+            bytes += N_CORNERS + N_SLICE_SORTED + 2*N_U_EDGES;
+        }
+        bytes += match self {
+            TableSet::Symmetric => PHASE1_SYM_SIZE + Symmetries::BYTES,
+            TableSet::TwistFlip | TableSet::Small => TWIST_SLICE_SIZE + FLIP_SLICE_SIZE,
+        };
+        bytes += match self {
+            TableSet::Symmetric | TableSet::TwistFlip => CORNERS_SLICE_SIZE + UD_EDGES_SLICE_SIZE,
+            TableSet::Small => N_CORNERS + N_UD_EDGES,
+        };
+        bytes
+    }
+
+    /// Biggest set that fits in `budget` bytes and works with `moves` and `metric`, None if
+    /// not even `Small` fits
+    pub fn for_budget(budget: usize, moves: &[Move], metric: Metric) -> Option<TableSet> {
+        TableSet::ALL.into_iter().find(|t| t.supports(moves, metric) && t.bytes(moves) <= budget)
+    }
+
+    /// Rough time to solve a random cube relative to `TwistFlip`, from the solve benchmark
+    /// (up to 21 moves, all 18 moves). The gap gets wider the shorter the solutions have to be
+    pub fn relative_time(self) -> f64 {
+        match self {
+            TableSet::Symmetric => 0.09,
+            TableSet::TwistFlip => 1.0,
+            TableSet::Small => 1.5,
+        }
+    }
+}
+
+impl Display for TableSet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            TableSet::Symmetric => "symmetric",
+            TableSet::TwistFlip => "twist-flip",
+            TableSet::Small => "small",
+        })
+    }
+}
+
+/// Entry `i` of a table with two entries a byte
+pub(crate) fn get_nibble(table: &[u8], i: usize) -> u8 {
+    table[i / 2] >> (4*(i & 1)) & 0xF
+}
+
+fn set_nibble(table: &mut [u8], i: usize, d: u8) {
+    let shift = 4*(i & 1);
+    table[i / 2] = table[i / 2] & !(0xF << shift) | d << shift;
+}
+
+/// Inverses of `moves`, pruning tables are searched outwards from solved so they step backwards
+fn inverse_moves(moves: &[usize]) -> Vec<usize> {
+    moves.iter().map(|&m| Move::from_index(m).inverse() as usize).collect()
+}
+
+/// Suffix to keep tables for different move sets apart, empty for all 18 moves
+#[cfg(feature = "std")]
+fn move_set_suffix(allowed: &[usize]) -> String {
+    if allowed.len() == N_MOVE {
+        return String::new();
+    }
+
+    let mask = allowed.iter().fold(0u32, |mask, &m| mask | 1 << m);
+    format!("_{:05x}", mask)
+}
+
+/// Search outwards from the solved state (index 0) to fill in a table of `n1*n2` entries.
+/// `step` takes an index into each coordinate and a face turn, returning the new pair of
+/// coordinates. Distances are in the cost of the axis moves so it works for any metric,
+/// entries that can't be reached with `moves` are left as `EMPTY`
+fn gen_prun_table(n1: usize, n2: usize, moves: &[AxisMove], step: impl Fn(usize, usize, usize) -> (usize, usize)) -> Vec<u8> {
+    let size = n1*n2;
+    let mut table = vec![EMPTY; size];
+    table[0] = 0;
+
+    // A pair of turns that costs as much as doing them separately can't make anything shorter
+    let inverses: Vec<(Vec<usize>, u16)> = moves.iter()
+        .filter(|am| am.moves.len() == 1 || moves.iter()
+            .filter(|single| single.moves.len() == 1 && am.moves.contains(&single.moves[0]))
+            .map(|single| single.cost)
+            .sum::<u16>() > am.cost)
+        .map(|am| (inverse_moves(&am.moves), am.cost))
+        .collect();
+
+    // Distances are only final once everything closer has been expanded, so go a level at a time
+    let mut depth = 0;
+    let mut max = 0;
+    while depth <= max && depth < EMPTY {
+        let mut changed = false;
+        for i in 0..size {
+            if table[i] != depth {
+                continue;
+            }
+            for (inverse, cost) in &inverses {
+                let (mut a, mut b) = (i / n2, i % n2);
+                for &m in inverse {
+                    (a, b) = step(a, b, m);
+                }
+
+                let d = (depth as usize + *cost as usize).min(EMPTY as usize - 1) as u8;
+                let j = n2*a + b;
+                if d < table[j] {
+                    table[j] = d;
+                    max = max.max(d);
+                    changed = true;
+                }
+            }
+        }
+        // Anything further is lumped in with the last level, which can take a few passes
+        if depth < EMPTY - 1 || !changed {
+            depth += 1;
+        }
+    }
+
+    table
+}
+
+/// Phase 1 table for corner twist and the position of the UD slice edges
+pub(crate) fn gen_twist_slice_prun_table(moves: &[AxisMove], twist_move: &[u16], slice_sorted_move: &[u16]) -> Vec<u8> {
+    gen_prun_table(N_TWIST, N_SLICE, moves, |twist, slice, m| {
+        (twist_move[N_MOVE*twist + m] as usize, slice_sorted_move[N_MOVE*N_PERM_4*slice + m] as usize / N_PERM_4)
+    })
+}
+
+/// Phase 1 table for edge flip and the position of the UD slice edges
+pub(crate) fn gen_flip_slice_prun_table(moves: &[AxisMove], flip_move: &[u16], slice_sorted_move: &[u16]) -> Vec<u8> {
+    gen_prun_table(N_FLIP, N_SLICE, moves, |flip, slice, m| {
+        (flip_move[N_MOVE*flip + m] as usize, slice_sorted_move[N_MOVE*N_PERM_4*slice + m] as usize / N_PERM_4)
+    })
+}
+
+/// Phase 2 table for corner permutation and the permutation of the UD slice edges
+pub(crate) fn gen_corners_slice_prun_table(moves: &[AxisMove], corners_move: &[u16], slice_sorted_move: &[u16]) -> Vec<u8> {
+    let moves: Vec<AxisMove> = moves.iter().filter(|am| am.is_phase2()).cloned().collect();
+    gen_prun_table(N_CORNERS, N_PERM_4, &moves, |corners, slice_sorted, m| {
+        (corners_move[N_MOVE*corners + m] as usize, slice_sorted_move[N_MOVE*slice_sorted + m] as usize)
+    })
+}
+
+/// Phase 2 table for the U/D edge permutation and the permutation of the UD slice edges
+pub(crate) fn gen_ud_edges_slice_prun_table(moves: &[AxisMove], ud_edges_move: &[u16], slice_sorted_move: &[u16]) -> Vec<u8> {
+    let moves: Vec<AxisMove> = moves.iter().filter(|am| am.is_phase2()).cloned().collect();
+    gen_prun_table(N_UD_EDGES, N_PERM_4, &moves, |ud_edges, slice_sorted, m| {
+        (ud_edges_move[N_MOVE*ud_edges + m] as usize, slice_sorted_move[N_MOVE*slice_sorted + m] as usize)
+    })
+}
+
+/// Phase 1 table for twist, flip and slice together, see `TableSet::Symmetric`. Too big to
+/// go through every entry for every depth from the front, so once most are filled in it looks
+/// for an already reached neighbour from each empty entry instead
+pub(crate) fn gen_phase1_sym_prun_table(symmetries: &Symmetries, twist_move: &[u16], flip_move: &[u16], slice_sorted_move: &[u16]) -> Vec<u8> {
+    let size = N_FLIPSLICE_CLASS*N_TWIST;
+    let mut table = vec![EMPTY_NIBBLE << 4 | EMPTY_NIBBLE; PHASE1_SYM_SIZE];
+    set_nibble(&mut table, 0, 0);
+    let mut filled = 1;
+
+    // The class representative might be symmetric, then other twists are the same cube
+    let fill = |table: &mut [u8], class: usize, twist: usize, d: u8| {
+        let mut n = 0;
+        for t in core::iter::once(twist).chain(symmetries.twins(class, twist)) {
+            if get_nibble(table, N_TWIST*class + t) == EMPTY_NIBBLE {
+                set_nibble(table, N_TWIST*class + t, d);
+                n += 1;
+            }
+        }
+        n
+    };
+
+    let mut depth = 0;
+    while filled < size && depth < EMPTY_NIBBLE - 1 {
+        let backwards = filled > size / 2;
+        for i in 0..size {
+            let d = get_nibble(&table, i);
+            if d != if backwards { EMPTY_NIBBLE } else { depth } {
+                continue;
+            }
+
+            let (class, twist) = (i / N_TWIST, i % N_TWIST);
+            let (flip, slice) = symmetries.rep(class);
+            for m in 0..N_MOVE {
+                let twist1 = twist_move[N_MOVE*twist + m] as usize;
+                let flip1 = flip_move[N_MOVE*flip + m] as usize;
+                let slice1 = slice_sorted_move[N_MOVE*N_PERM_4*slice + m] as usize / N_PERM_4;
+                let j = symmetries.index(twist1, flip1, slice1);
+
+                if backwards {
+                    if get_nibble(&table, j) == depth {
+                        filled += fill(&mut table, class, twist, depth + 1);
+                        break;
+                    }
+                } else if get_nibble(&table, j) == EMPTY_NIBBLE {
+                    filled += fill(&mut table, j / N_TWIST, j % N_TWIST, depth + 1);
+                }
+            }
+        }
+        depth += 1;
+    }
+
+    table
+}
+
+/// Phase 2 table for the corner permutation alone, see `TableSet::Small`
+pub(crate) fn gen_corners_prun_table(moves: &[AxisMove], corners_move: &[u16]) -> Vec<u8> {
+    let moves: Vec<AxisMove> = moves.iter().filter(|am| am.is_phase2()).cloned().collect();
+    gen_prun_table(N_CORNERS, 1, &moves, |corners, _, m| (corners_move[N_MOVE*corners + m] as usize, 0))
+}
+
+/// Phase 2 table for the U/D edge permutation alone, see `TableSet::Small`
+pub(crate) fn gen_ud_edges_prun_table(moves: &[AxisMove], ud_edges_move: &[u16]) -> Vec<u8> {
+    let moves: Vec<AxisMove> = moves.iter().filter(|am| am.is_phase2()).cloned().collect();
+    gen_prun_table(N_UD_EDGES, 1, &moves, |ud_edges, _, m| (ud_edges_move[N_MOVE*ud_edges + m] as usize, 0))
+}
+
+/// Generic function to load/generate a pruning table, same rules as the move tables
+#[cfg(feature = "std")]
+fn load_prun_table(path: PathBuf, size: usize, gen: impl Fn() -> Vec<u8>) -> Result<Vec<u8>> {
+    match fs::read(&path) {
+        Ok(table) if table.len() == size => Ok(table),
+        Ok(_) => Err(Error::CorruptTable(path)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let table = gen();
+            write_table(&path, &table)?;
+
+            Ok(table)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// `allowed` and `metric` are the move set and metric the table is generated for, see
+/// `Solver::with_options`
+#[cfg(feature = "std")]
+pub fn load_twist_slice_prun_table(dir: Option<&Path>, allowed: &[usize], metric: &Metric, twist_move: &[u16], slice_sorted_move: &[u16]) -> Result<Vec<u8>> {
+    let path = table_path(dir, &format!("prun_twist_slice{}{}", move_set_suffix(allowed), metric.suffix()));
+    load_prun_table(path, TWIST_SLICE_SIZE, || gen_twist_slice_prun_table(&metric.axis_moves(allowed), twist_move, slice_sorted_move))
+}
+
+#[cfg(feature = "std")]
+pub fn load_flip_slice_prun_table(dir: Option<&Path>, allowed: &[usize], metric: &Metric, flip_move: &[u16], slice_sorted_move: &[u16]) -> Result<Vec<u8>> {
+    let path = table_path(dir, &format!("prun_flip_slice{}{}", move_set_suffix(allowed), metric.suffix()));
+    load_prun_table(path, FLIP_SLICE_SIZE, || gen_flip_slice_prun_table(&metric.axis_moves(allowed), flip_move, slice_sorted_move))
+}
+
+#[cfg(feature = "std")]
+pub fn load_corners_slice_prun_table(dir: Option<&Path>, allowed: &[usize], metric: &Metric, corners_move: &[u16], slice_sorted_move: &[u16]) -> Result<Vec<u8>> {
+    let path = table_path(dir, &format!("prun_corners_slice{}{}", move_set_suffix(allowed), metric.suffix()));
+    load_prun_table(path, CORNERS_SLICE_SIZE, || gen_corners_slice_prun_table(&metric.axis_moves(allowed), corners_move, slice_sorted_move))
+}
+
+#[cfg(feature = "std")]
+pub fn load_ud_edges_slice_prun_table(dir: Option<&Path>, allowed: &[usize], metric: &Metric, ud_edges_move: &[u16], slice_sorted_move: &[u16]) -> Result<Vec<u8>> {
+    let path = table_path(dir, &format!("prun_ud_edges_slice{}{}", move_set_suffix(allowed), metric.suffix()));
+    load_prun_table(path, UD_EDGES_SLICE_SIZE, || gen_ud_edges_slice_prun_table(&metric.axis_moves(allowed), ud_edges_move, slice_sorted_move))
+}
+
+/// Symmetry reduced phase 1 table for all 18 moves in the half turn metric, generating it
+/// takes around a minute
+#[cfg(feature = "std")]
+pub fn load_phase1_sym_prun_table(dir: Option<&Path>, symmetries: &Symmetries, twist_move: &[u16], flip_move: &[u16], slice_sorted_move: &[u16]) -> Result<Vec<u8>> {
+    let path = table_path(dir, "prun_phase1_sym");
+    load_prun_table(path, PHASE1_SYM_SIZE, || gen_phase1_sym_prun_table(symmetries, twist_move, flip_move, slice_sorted_move))
+}
+
+/// Which values of a single coordinate can be reached from `solved` with `allowed`, not saved
+/// since it's quick to generate. Used to spot cubes that can't be solved with a restricted move set
+pub fn gen_reachable(n: usize, solved: usize, allowed: &[usize], coord_move: &[u16]) -> Vec<bool> {
+    let moves = inverse_moves(allowed);
+    let mut reachable = vec![false; n];
+    let mut stack = vec![solved];
+    reachable[solved] = true;
+
+    while let Some(a) = stack.pop() {
+        for &m in &moves {
+            let a1 = coord_move[N_MOVE*a + m] as usize;
+            if !reachable[a1] {
+                reachable[a1] = true;
+                stack.push(a1);
+            }
+        }
+    }
+
+    reachable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+    use Move::*;
+
+    const MB: usize = 1 << 20;
+
+    #[test]
+    fn nibbles() {
+        let mut table = vec![0xFF; 2];
+        set_nibble(&mut table, 1, 3);
+        set_nibble(&mut table, 2, 12);
+        assert_eq!(table, [0x3F, 0xFC]);
+        assert_eq!((0..4).map(|i| get_nibble(&table, i)).collect::<Vec<_>>(), [0xF, 3, 12, 0xF]);
+    }
+
+    #[test]
+    fn budget() {
+        let all: Vec<Move> = Move::iter().collect();
+        assert_eq!(TableSet::for_budget(64 << 30, &all, Metric::Half), Some(TableSet::Symmetric));
+        assert_eq!(TableSet::for_budget(64*MB, &all, Metric::Half), Some(TableSet::TwistFlip));
+        assert_eq!(TableSet::for_budget(7*MB, &all, Metric::Half), Some(TableSet::Small));
+        assert_eq!(TableSet::for_budget(MB, &all, Metric::Half), None);
+
+        // The symmetric table is only for the full move set in the half turn metric
+        assert_eq!(TableSet::for_budget(64 << 30, &all, Metric::Quarter), Some(TableSet::TwistFlip));
+        assert_eq!(TableSet::for_budget(64 << 30, &[R1, R2, R3, U1, U2, U3], Metric::Half), Some(TableSet::TwistFlip));
+
+        assert!(TableSet::ALL.windows(2).all(|w| w[0].bytes(&all) > w[1].bytes(&all)));
+        assert!(TableSet::ALL.windows(2).all(|w| w[0].relative_time() < w[1].relative_time()));
+    }
+
+    #[test]
+    fn small_tables_are_lower_bounds() {
+        let moves = Metric::Half.axis_moves(&(0..N_MOVE).collect::<Vec<_>>());
+        let corners_move = crate::moves::gen_corners_move_table();
+        let slice_sorted_move = crate::moves::gen_ud_move_table();
+        let corners = gen_corners_prun_table(&moves, &corners_move);
+        let corners_slice = gen_corners_slice_prun_table(&moves, &corners_move, &slice_sorted_move);
+        assert_eq!(corners[0], 0);
+        // Ignoring the slice edges can only make it closer
+        for c in 0..N_CORNERS {
+            let best = (0..N_PERM_4).map(|s| corners_slice[N_PERM_4*c + s]).min().unwrap();
+            assert_eq!(corners[c], best);
+        }
+    }
+
+    #[test]
+    fn expensive_custom_moves() {
+        // Two 200s don't fit in a byte, distances past 254 stay at 254
+        let metric = Metric::Custom([200; N_MOVE]);
+        let moves = metric.axis_moves(&(0..N_MOVE).collect::<Vec<_>>());
+        assert!(moves.iter().all(|am| am.cost == 200 * am.moves.len() as u16));
+        assert_eq!(metric.cost(&[R1, L1, U2]), 600);
+
+        let corners = gen_corners_prun_table(&moves, &crate::moves::gen_corners_move_table());
+        assert_eq!(corners[0], 0);
+        assert!(corners[1..].iter().all(|&d| (200..EMPTY).contains(&d)));
+    }
+}
@@ -0,0 +1,123 @@
+use std::env;
+
+use crate::{face::FaceCube, scheme::ColorScheme};
+
+/// How colours are written to the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    /// 24 bit colour escapes, most modern terminals
+    TrueColor,
+    /// xterm 256 colour palette
+    Ansi256,
+    /// No escapes, each sticker is its colour letter
+    Plain,
+}
+
+impl ColorMode {
+    /// Best guess from the environment, `NO_COLOR`, `COLORTERM` and `TERM`
+    pub fn from_env() -> Self {
+        if env::var_os("NO_COLOR").is_some() {
+            return ColorMode::Plain;
+        }
+        match env::var("COLORTERM") {
+            Ok(c) if c == "truecolor" || c == "24bit" => return ColorMode::TrueColor,
+            _ => {}
+        }
+        match env::var("TERM") {
+            Ok(t) if t.contains("256color") => ColorMode::Ansi256,
+            _ => ColorMode::Plain,
+        }
+    }
+}
+
+/// Draws a `FaceCube` as an unfolded net in the terminal
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    pub mode: ColorMode,
+    pub scheme: ColorScheme,
+    /// What each sticker is drawn as in colour, should be the same width for every sticker
+    pub glyph: String,
+}
+
+impl Renderer {
+    pub fn new(mode: ColorMode) -> Self {
+        Renderer { mode, scheme: ColorScheme::default(), glyph: "██".to_string() }
+    }
+
+    /// Same layout as `FaceCube`'s `Display`, one line per row of stickers
+    pub fn render(&self, cube: &FaceCube) -> String {
+        let width = match self.mode {
+            ColorMode::Plain => 1,
+            _ => self.glyph.chars().count(),
+        };
+
+        let mut out = String::new();
+        for row in cube.net() {
+            // Don't pad after the last sticker
+            let len = row.iter().rposition(|c| c.is_some()).map_or(0, |i| i + 1);
+            for cell in &row[..len] {
+                let Some(c) = cell else {
+                    out.push_str(&" ".repeat(width));
+                    continue;
+                };
+                let s = self.scheme.sticker(*c);
+                match self.mode {
+                    ColorMode::TrueColor => {
+                        let (r, g, b) = s.rgb();
+                        out.push_str(&format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, self.glyph));
+                    }
+                    ColorMode::Ansi256 => {
+                        out.push_str(&format!("\x1b[38;5;{}m{}\x1b[0m", s.ansi256(), self.glyph));
+                    }
+                    ColorMode::Plain => out.push(s.letter()),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer::new(ColorMode::from_env())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common::Move, cubie::CubieCube};
+
+    #[test]
+    fn plain_net() {
+        let net = Renderer::new(ColorMode::Plain).render(&FaceCube::new());
+        let expected = "   WWW\n   WWW\n   WWW\n\
+                        OOOGGGRRRBBB\nOOOGGGRRRBBB\nOOOGGGRRRBBB\n\
+                        \x20  YYY\n   YYY\n   YYY\n";
+        assert_eq!(net, expected);
+    }
+
+    #[test]
+    fn plain_net_after_move() {
+        let mut cc = CubieCube::default();
+        cc.apply_move(Move::U1);
+        let net = Renderer::new(ColorMode::Plain).render(&FaceCube::from_cubie(&cc));
+        // U turns the top row of the side faces to the left
+        assert_eq!(net.lines().nth(3), Some("GGGRRRBBBOOO"));
+        assert_eq!(net.lines().nth(4), Some("OOOGGGRRRBBB"));
+    }
+
+    #[test]
+    fn colour_escapes() {
+        let mut renderer = Renderer::new(ColorMode::TrueColor);
+        renderer.glyph = "#".to_string();
+        let net = renderer.render(&FaceCube::new());
+        assert!(net.starts_with("   \x1b[38;2;255;255;255m#\x1b[0m"));
+        assert_eq!(net.matches('#').count(), 54);
+
+        renderer.mode = ColorMode::Ansi256;
+        let net = renderer.render(&FaceCube::new());
+        assert_eq!(net.matches("\x1b[38;5;").count(), 54);
+    }
+}
@@ -0,0 +1,211 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Display;
+
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{common::Color, error::Result, face::FaceCube};
+
+/// The actual colours on a cube, `Color` is which face a sticker belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+pub enum Sticker {
+    White,
+    Yellow,
+    Green,
+    Blue,
+    Red,
+    Orange,
+}
+
+impl Sticker {
+    pub fn letter(self) -> char {
+        match self {
+            Sticker::White => 'W',
+            Sticker::Yellow => 'Y',
+            Sticker::Green => 'G',
+            Sticker::Blue => 'B',
+            Sticker::Red => 'R',
+            Sticker::Orange => 'O',
+        }
+    }
+
+    /// Roughly the usual sticker colours
+    pub fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            Sticker::White => (255, 255, 255),
+            Sticker::Yellow => (255, 213, 0),
+            Sticker::Green => (0, 155, 72),
+            Sticker::Blue => (0, 70, 173),
+            Sticker::Red => (183, 18, 52),
+            Sticker::Orange => (255, 88, 0),
+        }
+    }
+
+    /// Closest colour in the xterm 256 colour palette
+    pub fn ansi256(self) -> u8 {
+        match self {
+            Sticker::White => 231,
+            Sticker::Yellow => 220,
+            Sticker::Green => 28,
+            Sticker::Blue => 25,
+            Sticker::Red => 124,
+            Sticker::Orange => 202,
+        }
+    }
+
+    /// Either case
+    pub fn from_letter(c: char) -> Option<Sticker> {
+        Sticker::iter().find(|s| s.letter() == c.to_ascii_uppercase())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemeError {
+    /// Not one of W, Y, G, B, R, O
+    InvalidLetter(char),
+    /// Facelet strings are 54 letters
+    WrongLength(usize),
+    /// Each face needs its own colour, e.g. two white centres
+    DuplicateColor(Sticker),
+    /// Colour that isn't in the scheme
+    UnknownColor(Sticker),
+}
+
+impl Display for SchemeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SchemeError::InvalidLetter(c) => write!(f, "invalid colour `{}`", c),
+            SchemeError::WrongLength(n) => write!(f, "expected 54 facelets, got {}", n),
+            SchemeError::DuplicateColor(s) => write!(f, "{:?} is used for more than one face", s),
+            SchemeError::UnknownColor(s) => write!(f, "{:?} isn't in the colour scheme", s),
+        }
+    }
+}
+
+impl core::error::Error for SchemeError {}
+
+/// Which colour each face is, indexed by `Color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorScheme {
+    faces: [Sticker; 6],
+}
+
+impl ColorScheme {
+    /// Colours of the U, R, F, D, L and B faces, which must all be different
+    pub fn new(faces: [Sticker; 6]) -> Result<Self> {
+        for (i, &s) in faces.iter().enumerate() {
+            if faces[..i].contains(&s) {
+                return Err(SchemeError::DuplicateColor(s).into());
+            }
+        }
+        Ok(ColorScheme { faces })
+    }
+
+    /// White top, green front, red right and yellow opposite white
+    pub fn western() -> Self {
+        use Sticker::*;
+        ColorScheme { faces: [White, Red, Green, Yellow, Orange, Blue] }
+    }
+
+    /// Like the western scheme but blue is opposite white and yellow opposite green
+    pub fn japanese() -> Self {
+        use Sticker::*;
+        ColorScheme { faces: [White, Red, Green, Blue, Orange, Yellow] }
+    }
+
+    /// Takes the scheme from the centre stickers of a 54 letter colour string, in the same
+    /// facelet order as `FaceCube`
+    pub fn detect(s: &str) -> Result<Self> {
+        let stickers = parse_stickers(s)?;
+        let mut faces = [Sticker::White; 6];
+        for (i, face) in faces.iter_mut().enumerate() {
+            *face = stickers[9*i + 4];
+        }
+        ColorScheme::new(faces)
+    }
+
+    pub fn sticker(&self, c: Color) -> Sticker {
+        self.faces[c as usize]
+    }
+
+    pub fn color(&self, s: Sticker) -> Option<Color> {
+        Color::iter().find(|&c| self.sticker(c) == s)
+    }
+
+    /// Reads a 54 letter colour string like `WWWWWWWWWRRR...`
+    pub fn parse(&self, s: &str) -> Result<FaceCube> {
+        let stickers = parse_stickers(s)?;
+        let mut cube = FaceCube::new();
+        for (face, &s) in cube.faces.iter_mut().zip(&stickers) {
+            *face = self.color(s).ok_or(SchemeError::UnknownColor(s))?;
+        }
+        Ok(cube)
+    }
+
+    /// Colour string of `cube`, the opposite of `parse`
+    pub fn format(&self, cube: &FaceCube) -> String {
+        cube.faces.iter().map(|&c| self.sticker(c).letter()).collect()
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme::western()
+    }
+}
+
+fn parse_stickers(s: &str) -> core::result::Result<Vec<Sticker>, SchemeError> {
+    let stickers = s.chars()
+        .map(|c| Sticker::from_letter(c).ok_or(SchemeError::InvalidLetter(c)))
+        .collect::<core::result::Result<Vec<_>, _>>()?;
+    if stickers.len() != 54 {
+        return Err(SchemeError::WrongLength(stickers.len()));
+    }
+    Ok(stickers)
+}
+
+impl FaceCube {
+    /// Parses a colour string whatever the scheme, taking it from the centres
+    pub fn from_colors(s: &str) -> Result<FaceCube> {
+        ColorScheme::detect(s)?.parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common::Move, cubie::CubieCube, error::Error};
+
+    const SOLVED: &str = "WWWWWWWWWRRRRRRRRRGGGGGGGGGYYYYYYYYYOOOOOOOOOBBBBBBBBB";
+
+    #[test]
+    fn western_round_trip() {
+        let scheme = ColorScheme::western();
+        let cube = scheme.parse(SOLVED).unwrap();
+        assert!(cube == FaceCube::new());
+        assert_eq!(scheme.format(&cube), SOLVED);
+
+        let mut cc = CubieCube::default();
+        cc.apply_moves(&[Move::R1, Move::U1, Move::F3]);
+        let cube = FaceCube::from_cubie(&cc);
+        assert!(scheme.parse(&scheme.format(&cube)).unwrap() == cube);
+    }
+
+    #[test]
+    fn detects_scheme() {
+        assert_eq!(ColorScheme::detect(SOLVED).unwrap(), ColorScheme::western());
+
+        let japanese = ColorScheme::japanese().format(&FaceCube::new());
+        assert_eq!(ColorScheme::detect(&japanese).unwrap(), ColorScheme::japanese());
+        assert!(FaceCube::from_colors(&japanese.to_lowercase()).unwrap() == FaceCube::new());
+    }
+
+    #[test]
+    fn bad_input() {
+        use Sticker::*;
+        assert!(matches!(ColorScheme::western().parse("WWW"), Err(Error::InvalidColors(SchemeError::WrongLength(3)))));
+        assert!(matches!(ColorScheme::detect(&SOLVED.replace('O', "P")), Err(Error::InvalidColors(SchemeError::InvalidLetter('P')))));
+        assert!(matches!(ColorScheme::detect(&SOLVED.replace('O', "W")), Err(Error::InvalidColors(SchemeError::DuplicateColor(White)))));
+        assert!(matches!(ColorScheme::new([White, White, Green, Yellow, Orange, Blue]), Err(Error::InvalidColors(SchemeError::DuplicateColor(White)))));
+    }
+}
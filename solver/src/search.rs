@@ -0,0 +1,1220 @@
+use alloc::{borrow::Cow, format, string::{String, ToString}, vec::Vec};
+use core::fmt::Display;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::{path::Path, time::Instant};
+
+use rand::Rng;
+use strum::IntoEnumIterator;
+
+use crate::{
+    common::{Move, N_CORNERS, N_FLIP, N_MOVE, N_PERM_4, N_SLICE, N_SLICE_SORTED, N_TWIST, N_U_EDGES, N_UD_EDGES},
+    cubie::CubieCube,
+    error::{Error, Result},
+    metric::{AxisMove, Metric},
+    moves::{
+        gen_corners_move_table, gen_d_edges_move_table, gen_flip_move_table, gen_twist_move_table,
+        gen_u_edges_move_table, gen_ud_edges_move_table, gen_ud_move_table, valid_move_table,
+    },
+    packed::PackedCube,
+    pruning::{
+        CORNERS_SLICE_SIZE, EMPTY, FLIP_SLICE_SIZE, TWIST_SLICE_SIZE, TableSet, UD_EDGES_SLICE_SIZE,
+        gen_corners_prun_table, gen_corners_slice_prun_table, gen_flip_slice_prun_table, gen_reachable,
+        gen_phase1_sym_prun_table, gen_twist_slice_prun_table, gen_ud_edges_prun_table, gen_ud_edges_slice_prun_table,
+        get_nibble,
+    },
+    symmetry::Symmetries,
+};
+#[cfg(feature = "std")]
+use crate::{
+    moves::{
+        load_corners_move_table, load_d_edges_move_table, load_flip_move_table, load_twist_move_table,
+        load_u_edges_move_table, load_ud_edges_move_table, load_ud_move_table,
+    },
+    pruning::{
+        load_corners_slice_prun_table, load_flip_slice_prun_table, load_phase1_sym_prun_table,
+        load_twist_slice_prun_table, load_ud_edges_slice_prun_table,
+    },
+};
+
+/// A sequence of face turns that solves a cube
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Solution {
+    pub moves: Vec<Move>,
+    /// Length in the solver's metric
+    pub cost: usize,
+}
+
+impl Solution {
+    /// Length in the half turn metric
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+}
+
+/// Space separated moves, e.g. `R U2 F'`
+impl Display for Solution {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let moves: Vec<String> = self.moves.iter().map(|m| m.to_string()).collect();
+        f.write_str(&moves.join(" "))
+    }
+}
+
+/// Result of `Solver::solve_report`
+#[derive(Debug, Clone)]
+pub struct SolveReport {
+    /// `None` if there's no solution within the max length
+    pub solution: Option<Solution>,
+    pub elapsed: Duration,
+    pub stats: SearchStats,
+}
+
+/// Work done by a search
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Calls to phase1
+    pub phase1_nodes: u64,
+    /// Calls to phase2
+    pub phase2_nodes: u64,
+    /// Phase 1 solutions phase 2 was started from
+    pub phase1_solutions: u64,
+    /// Move table entries read
+    pub move_lookups: u64,
+    /// Pruning table entries read
+    pub prune_lookups: u64,
+    /// Moves cut off by the pruning tables, indexed by the number of moves already made
+    pub pruned: Vec<u64>,
+    pub phase1_elapsed: Duration,
+    pub phase2_elapsed: Duration,
+}
+
+impl SearchStats {
+    /// Nodes visited in both phases
+    pub fn nodes(&self) -> u64 {
+        self.phase1_nodes + self.phase2_nodes
+    }
+
+    /// Add another search's work to this one, for totals over many searches
+    pub fn add(&mut self, other: &SearchStats) {
+        self.phase1_nodes += other.phase1_nodes;
+        self.phase2_nodes += other.phase2_nodes;
+        self.phase1_solutions += other.phase1_solutions;
+        self.move_lookups += other.move_lookups;
+        self.prune_lookups += other.prune_lookups;
+        if self.pruned.len() < other.pruned.len() {
+            self.pruned.resize(other.pruned.len(), 0);
+        }
+        for (a, b) in self.pruned.iter_mut().zip(&other.pruned) {
+            *a += b;
+        }
+        self.phase1_elapsed += other.phase1_elapsed;
+        self.phase2_elapsed += other.phase2_elapsed;
+    }
+
+    fn prune(&mut self, depth: usize) {
+        if self.pruned.len() <= depth {
+            self.pruned.resize(depth + 1, 0);
+        }
+        self.pruned[depth] += 1;
+    }
+}
+
+/// Live events from a search, see `Solver::solve_observed`. Everything does nothing by default
+/// Called from inside the search so anything slow slows it down
+pub trait SearchObserver {
+    /// Starting on phase 1 sequences of `depth` moves (in the solver's metric)
+    fn phase1_depth(&mut self, _depth: usize, _stats: &SearchStats) {}
+    /// `moves` take the cube into the phase 2 subgroup, phase 2 carries on from there
+    fn phase1_solution(&mut self, _moves: &[Move]) {}
+    fn solution(&mut self, _solution: &Solution, _stats: &SearchStats) {}
+    /// Gave up because of one of the limits
+    fn stopped(&mut self, _reason: &Error, _stats: &SearchStats) {}
+}
+
+/// Observes nothing
+impl SearchObserver for () {}
+
+/// Limits on a single solve, see `Solver::solve_with`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Limits<'a> {
+    /// Give up with `Error::Timeout` after this long
+    #[cfg(feature = "std")]
+    pub timeout: Option<Duration>,
+    /// Give up with `Error::Cancelled` once this is set, e.g. from another thread
+    pub cancel: Option<&'a AtomicBool>,
+}
+
+/// Tables for `Solver::from_tables`, the same as the files `Solver::new` writes for the full
+/// move set. Move tables are one entry per coordinate and move, pruning tables one per pair of
+/// coordinates, see `moves` and `pruning`
+#[derive(Debug, Clone, Copy)]
+pub struct SolverTables {
+    pub twist_move: &'static [u16],
+    pub flip_move: &'static [u16],
+    pub slice_sorted_move: &'static [u16],
+    pub corners_move: &'static [u16],
+    pub ud_edges_move: &'static [u16],
+
+    pub twist_slice_prun: &'static [u8],
+    pub flip_slice_prun: &'static [u8],
+    pub corners_slice_prun: &'static [u8],
+    pub ud_edges_slice_prun: &'static [u8],
+}
+
+// Either loaded or generated, or passed in with `from_tables`
+type Table<T> = Cow<'static, [T]>;
+
+/// Face turns a scramble can take, see `Solver::scramble`
+const SCRAMBLE_MOVES: usize = 24;
+
+/// Two-phase solver, holds all the move and pruning tables
+pub struct Solver {
+    twist_move: Table<u16>,
+    flip_move: Table<u16>,
+    slice_sorted_move: Table<u16>,
+    corners_move: Table<u16>,
+    ud_edges_move: Table<u16>,
+
+    phase1_prun: Phase1Prun,
+    phase2_prun: Phase2Prun,
+
+    metric: Metric,
+    phase1_moves: Vec<AxisMove>,
+    phase2_moves: Vec<AxisMove>,
+
+    // Coordinate values the move set can reach, None if it's all 18 moves
+    reachable: Option<Reachable>,
+}
+
+/// Times the stats, without std there's no clock so everything takes zero time
+#[derive(Clone, Copy)]
+struct Clock {
+    #[cfg(feature = "std")]
+    start: Instant,
+}
+
+impl Clock {
+    fn now() -> Self {
+        Clock {
+            #[cfg(feature = "std")]
+            start: Instant::now(),
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        #[cfg(feature = "std")]
+        return self.start.elapsed();
+        #[cfg(not(feature = "std"))]
+        Duration::ZERO
+    }
+}
+
+/// Phase 1 pruning tables, see `TableSet`
+enum Phase1Prun {
+    TwistFlip { twist_slice: Table<u8>, flip_slice: Table<u8> },
+    // Two entries a byte
+    Symmetric { symmetries: Symmetries, table: Vec<u8> },
+}
+
+/// Phase 2 pruning tables, see `TableSet`
+enum Phase2Prun {
+    Slice { corners_slice: Table<u8>, ud_edges_slice: Table<u8> },
+    Small { corners: Vec<u8>, ud_edges: Vec<u8> },
+}
+
+/// `SolverTables` but owned or borrowed
+struct Tables {
+    twist_move: Table<u16>,
+    flip_move: Table<u16>,
+    slice_sorted_move: Table<u16>,
+    corners_move: Table<u16>,
+    ud_edges_move: Table<u16>,
+    phase1_prun: Phase1Prun,
+    phase2_prun: Phase2Prun,
+}
+
+/// Sorted move indices without repeats
+fn allowed(moves: &[Move]) -> Vec<usize> {
+    let mut allowed: Vec<usize> = moves.iter().map(|&m| m as usize).collect();
+    allowed.sort();
+    allowed.dedup();
+    allowed
+}
+
+struct Reachable {
+    corners: Vec<bool>,
+    slice_sorted: Vec<bool>,
+    u_edges: Vec<bool>,
+    d_edges: Vec<bool>,
+}
+
+impl Solver {
+    /// Load all the tables, generating any that are missing
+    /// `dir` is passed through to the table loaders
+    #[cfg(feature = "std")]
+    pub fn new(dir: Option<&Path>) -> Result<Self> {
+        let moves: Vec<Move> = Move::iter().collect();
+        Self::with_options(dir, &moves, Metric::Half)
+    }
+
+    /// Solver that only uses `moves`, e.g. `[R1, R2, R3, U1, U2, U3]` for <R, U>
+    /// Pruning tables are generated for the move set and saved separately from the full ones
+    /// Cubes outside the subgroup are `Unreachable` as far as `check_reachable` can tell,
+    /// the rest of them have no solution
+    #[cfg(feature = "std")]
+    pub fn with_moves(dir: Option<&Path>, moves: &[Move]) -> Result<Self> {
+        Self::with_options(dir, moves, Metric::Half)
+    }
+
+    /// Solver that minimises solution length in `metric` rather than counting face turns
+    #[cfg(feature = "std")]
+    pub fn with_metric(dir: Option<&Path>, metric: Metric) -> Result<Self> {
+        let moves: Vec<Move> = Move::iter().collect();
+        Self::with_options(dir, &moves, metric)
+    }
+
+    /// Solver for a move set and a metric, see `with_moves` and `with_metric`
+    #[cfg(feature = "std")]
+    pub fn with_options(dir: Option<&Path>, moves: &[Move], metric: Metric) -> Result<Self> {
+        Self::with_table_set(dir, moves, metric, TableSet::TwistFlip)
+    }
+
+    /// `with_options` using the pruning tables in `tables`. `Symmetric` falls back to
+    /// `TwistFlip` for move sets and metrics it doesn't support, see `table_set`
+    #[cfg(feature = "std")]
+    pub fn with_table_set(dir: Option<&Path>, moves: &[Move], metric: Metric, tables: TableSet) -> Result<Self> {
+        metric.check()?;
+        let allowed = allowed(moves);
+        let tables = if tables.supports(moves, metric) { tables } else { TableSet::TwistFlip };
+
+        let twist_move = load_twist_move_table(dir)?;
+        let flip_move = load_flip_move_table(dir)?;
+        let slice_sorted_move = load_ud_move_table(dir)?;
+        let corners_move = load_corners_move_table(dir)?;
+        let ud_edges_move = load_ud_edges_move_table(dir)?;
+
+        let phase1_prun = match tables {
+            TableSet::Symmetric => {
+                let symmetries = Symmetries::new();
+                let table = load_phase1_sym_prun_table(dir, &symmetries, &twist_move, &flip_move, &slice_sorted_move)?;
+                Phase1Prun::Symmetric { symmetries, table }
+            }
+            TableSet::TwistFlip | TableSet::Small => Phase1Prun::TwistFlip {
+                twist_slice: load_twist_slice_prun_table(dir, &allowed, &metric, &twist_move, &slice_sorted_move)?.into(),
+                flip_slice: load_flip_slice_prun_table(dir, &allowed, &metric, &flip_move, &slice_sorted_move)?.into(),
+            },
+        };
+        let phase2_prun = match tables {
+            TableSet::Symmetric | TableSet::TwistFlip => Phase2Prun::Slice {
+                corners_slice: load_corners_slice_prun_table(dir, &allowed, &metric, &corners_move, &slice_sorted_move)?.into(),
+                ud_edges_slice: load_ud_edges_slice_prun_table(dir, &allowed, &metric, &ud_edges_move, &slice_sorted_move)?.into(),
+            },
+            // Quick enough to not bother saving
+            TableSet::Small => {
+                let axis_moves = metric.axis_moves(&allowed);
+                Phase2Prun::Small {
+                    corners: gen_corners_prun_table(&axis_moves, &corners_move),
+                    ud_edges: gen_ud_edges_prun_table(&axis_moves, &ud_edges_move),
+                }
+            }
+        };
+
+        let u_d_edges_move = if allowed.len() == N_MOVE {
+            None
+        } else {
+            Some((load_u_edges_move_table(dir)?, load_d_edges_move_table(dir)?))
+        };
+
+        Ok(Self::assemble(allowed, metric, Tables {
+            twist_move: twist_move.into(),
+            flip_move: flip_move.into(),
+            slice_sorted_move: slice_sorted_move.into(),
+            corners_move: corners_move.into(),
+            ud_edges_move: ud_edges_move.into(),
+            phase1_prun,
+            phase2_prun,
+        }, u_d_edges_move))
+    }
+
+    /// `with_table_set` with the fastest tables that fit in `budget` bytes, see
+    /// `TableSet::for_budget`. `table_set` and `table_bytes` say what it went with
+    #[cfg(feature = "std")]
+    pub fn with_budget(dir: Option<&Path>, moves: &[Move], metric: Metric, budget: usize) -> Result<Self> {
+        let tables = TableSet::for_budget(budget, moves, metric)
+            .ok_or(Error::OverBudget { budget, needed: TableSet::Small.bytes(moves) })?;
+        Self::with_table_set(dir, moves, metric, tables)
+    }
+
+    /// `with_options` without files, every table is generated in memory. Takes a few seconds
+    /// and about 8 MB for the full move set
+    pub fn generate(moves: &[Move], metric: Metric) -> Result<Self> {
+        Self::generate_table_set(moves, metric, TableSet::TwistFlip)
+    }
+
+    /// `generate` with the pruning tables in `tables`, see `with_table_set`
+    pub fn generate_table_set(moves: &[Move], metric: Metric, tables: TableSet) -> Result<Self> {
+        metric.check()?;
+        let allowed = allowed(moves);
+        let axis_moves = metric.axis_moves(&allowed);
+        let tables = if tables.supports(moves, metric) { tables } else { TableSet::TwistFlip };
+
+        let twist_move = gen_twist_move_table();
+        let flip_move = gen_flip_move_table();
+        let slice_sorted_move = gen_ud_move_table();
+        let corners_move = gen_corners_move_table();
+        let ud_edges_move = gen_ud_edges_move_table();
+
+        let phase1_prun = match tables {
+            TableSet::Symmetric => {
+                let symmetries = Symmetries::new();
+                let table = gen_phase1_sym_prun_table(&symmetries, &twist_move, &flip_move, &slice_sorted_move);
+                Phase1Prun::Symmetric { symmetries, table }
+            }
+            TableSet::TwistFlip | TableSet::Small => Phase1Prun::TwistFlip {
+                twist_slice: gen_twist_slice_prun_table(&axis_moves, &twist_move, &slice_sorted_move).into(),
+                flip_slice: gen_flip_slice_prun_table(&axis_moves, &flip_move, &slice_sorted_move).into(),
+            },
+        };
+        let phase2_prun = match tables {
+            TableSet::Symmetric | TableSet::TwistFlip => Phase2Prun::Slice {
+                corners_slice: gen_corners_slice_prun_table(&axis_moves, &corners_move, &slice_sorted_move).into(),
+                ud_edges_slice: gen_ud_edges_slice_prun_table(&axis_moves, &ud_edges_move, &slice_sorted_move).into(),
+            },
+            TableSet::Small => Phase2Prun::Small {
+                corners: gen_corners_prun_table(&axis_moves, &corners_move),
+                ud_edges: gen_ud_edges_prun_table(&axis_moves, &ud_edges_move),
+            },
+        };
+
+        let u_d_edges_move = if allowed.len() == N_MOVE {
+            None
+        } else {
+            Some((gen_u_edges_move_table(), gen_d_edges_move_table()))
+        };
+
+        Ok(Self::assemble(allowed, metric, Tables {
+            twist_move: twist_move.into(),
+            flip_move: flip_move.into(),
+            slice_sorted_move: slice_sorted_move.into(),
+            corners_move: corners_move.into(),
+            ud_edges_move: ud_edges_move.into(),
+            phase1_prun,
+            phase2_prun,
+        }, u_d_edges_move))
+    }
+
+    /// Solver for all 18 moves using tables that are already in memory, e.g. in flash on a
+    /// microcontroller. The pruning tables have to be the ones for `metric`
+    /// Nothing is copied, only checked so a bad table can't make the search panic
+    pub fn from_tables(tables: SolverTables, metric: Metric) -> Result<Self> {
+        metric.check()?;
+        let move_tables = [
+            ("twist_move", tables.twist_move, N_TWIST),
+            ("flip_move", tables.flip_move, N_FLIP),
+            ("slice_sorted_move", tables.slice_sorted_move, N_SLICE_SORTED),
+            ("corners_move", tables.corners_move, N_CORNERS),
+            ("ud_edges_move", tables.ud_edges_move, N_UD_EDGES),
+        ];
+        for (name, table, n) in move_tables {
+            if !valid_move_table(table, n) {
+                return Err(Error::InvalidTable(name));
+            }
+        }
+        let prun_tables = [
+            ("twist_slice_prun", tables.twist_slice_prun, TWIST_SLICE_SIZE),
+            ("flip_slice_prun", tables.flip_slice_prun, FLIP_SLICE_SIZE),
+            ("corners_slice_prun", tables.corners_slice_prun, CORNERS_SLICE_SIZE),
+            ("ud_edges_slice_prun", tables.ud_edges_slice_prun, UD_EDGES_SLICE_SIZE),
+        ];
+        for (name, table, size) in prun_tables {
+            if table.len() != size {
+                return Err(Error::InvalidTable(name));
+            }
+        }
+
+        let moves: Vec<Move> = Move::iter().collect();
+        Ok(Self::assemble(allowed(&moves), metric, Tables {
+            twist_move: tables.twist_move.into(),
+            flip_move: tables.flip_move.into(),
+            slice_sorted_move: tables.slice_sorted_move.into(),
+            corners_move: tables.corners_move.into(),
+            ud_edges_move: tables.ud_edges_move.into(),
+            phase1_prun: Phase1Prun::TwistFlip {
+                twist_slice: tables.twist_slice_prun.into(),
+                flip_slice: tables.flip_slice_prun.into(),
+            },
+            phase2_prun: Phase2Prun::Slice {
+                corners_slice: tables.corners_slice_prun.into(),
+                ud_edges_slice: tables.ud_edges_slice_prun.into(),
+            },
+        }, None))
+    }
+
+    /// Shared end of the constructors, `u_d_edges_move` are only needed for a restricted move set
+    fn assemble(allowed: Vec<usize>, metric: Metric, tables: Tables, u_d_edges_move: Option<(Vec<u16>, Vec<u16>)>) -> Self {
+        let phase1_moves = metric.axis_moves(&allowed);
+        let phase2_moves = phase1_moves.iter().filter(|am| am.is_phase2()).cloned().collect();
+
+        let reachable = u_d_edges_move.map(|(u_edges_move, d_edges_move)| {
+            let solved = CubieCube::default();
+            Reachable {
+                corners: gen_reachable(N_CORNERS, 0, &allowed, &tables.corners_move),
+                slice_sorted: gen_reachable(N_SLICE_SORTED, 0, &allowed, &tables.slice_sorted_move),
+                u_edges: gen_reachable(N_U_EDGES, solved.get_u_edges() as usize, &allowed, &u_edges_move),
+                d_edges: gen_reachable(N_U_EDGES, solved.get_d_edges() as usize, &allowed, &d_edges_move),
+            }
+        });
+
+        let Tables {
+            twist_move,
+            flip_move,
+            slice_sorted_move,
+            corners_move,
+            ud_edges_move,
+            phase1_prun,
+            phase2_prun,
+        } = tables;
+        Solver {
+            twist_move,
+            flip_move,
+            slice_sorted_move,
+            corners_move,
+            ud_edges_move,
+            phase1_prun,
+            phase2_prun,
+            metric,
+            phase1_moves,
+            phase2_moves,
+            reachable,
+        }
+    }
+
+    /// Check the cube can be solved with the solver's moves at all. Only looks at one
+    /// coordinate at a time so with a restricted move set a cube that passes may still be
+    /// unsolvable, and solving it gives `Ok(None)` however long a solution is allowed to be
+    /// The cube should already have passed `CubieCube::verify`
+    pub fn check_reachable(&self, cube: &CubieCube) -> Result<()> {
+        let twist = cube.get_twist() as usize;
+        let flip = cube.get_flip() as usize;
+        let slice_sorted = cube.get_slice_sorted() as usize;
+        if self.phase1_dist(twist, flip, slice_sorted) == EMPTY as usize {
+            return Err(Error::Unreachable);
+        }
+
+        if let Some(r) = &self.reachable {
+            if !r.corners[cube.get_corners() as usize]
+                || !r.slice_sorted[slice_sorted]
+                || !r.u_edges[cube.get_u_edges() as usize]
+                || !r.d_edges[cube.get_d_edges() as usize] {
+                return Err(Error::Unreachable);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    /// Which pruning tables the solver has
+    pub fn table_set(&self) -> TableSet {
+        match (&self.phase1_prun, &self.phase2_prun) {
+            (Phase1Prun::Symmetric { .. }, _) => TableSet::Symmetric,
+            (_, Phase2Prun::Small { .. }) => TableSet::Small,
+            _ => TableSet::TwistFlip,
+        }
+    }
+
+    /// One line on the tables for logs, e.g. `twist-flip tables, 7.2 MB, 1x the solve time of twist-flip`
+    /// MB are 2^20 bytes, the same as budgets usually are
+    pub fn table_summary(&self) -> String {
+        let set = self.table_set();
+        format!("{} tables, {:.1} MB, {}x the solve time of {}", set, self.table_bytes() as f64 / (1 << 20) as f64, set.relative_time(), TableSet::TwistFlip)
+    }
+
+    /// Bytes of tables the solver holds, the same as `TableSet::bytes` for its move set
+    pub fn table_bytes(&self) -> usize {
+        let move_tables = [&self.twist_move, &self.flip_move, &self.slice_sorted_move, &self.corners_move, &self.ud_edges_move];
+        let mut bytes = move_tables.iter().map(|t| 2*t.len()).sum();
+        bytes += match &self.phase1_prun {
+            Phase1Prun::TwistFlip { twist_slice, flip_slice } => twist_slice.len() + flip_slice.len(),
+            Phase1Prun::Symmetric { table, .. } => table.len() + Symmetries::BYTES,
+        };
+        bytes += match &self.phase2_prun {
+            Phase2Prun::Slice { corners_slice, ud_edges_slice } => corners_slice.len() + ud_edges_slice.len(),
+            Phase2Prun::Small { corners, ud_edges } => corners.len() + ud_edges.len(),
+        };
+        if let Some(r) = &self.reachable {
+            bytes += r.corners.len() + r.slice_sorted.len() + r.u_edges.len() + r.d_edges.len();
+        }
+        bytes
+    }
+
+    /// Find a solution of at most `max_length` moves, not necessarily the shortest
+    /// Lengths are in the solver's metric
+    pub fn solve(&self, cube: &CubieCube, max_length: usize) -> Result<Option<Solution>> {
+        Ok(self.solve_all(cube, max_length, 1)?.pop())
+    }
+
+    /// Find a sequence of at most `max_length` moves that turns `start` into `goal`
+    /// Solves `goal^-1 * start`, since applying the solution to it gives the identity
+    pub fn solve_to(&self, start: &CubieCube, goal: &CubieCube, max_length: usize) -> Result<Option<Solution>> {
+        self.solve(&(goal.inverse() * start.clone()), max_length)
+    }
+
+    /// Moves that take a solved cube to a uniformly random one, the inverse of its solution
+    /// Gives up with `Timeout` or `Cancelled` when it hits one of `limits`
+    pub fn scramble(&self, rng: &mut impl Rng, limits: &Limits) -> Result<(CubieCube, Vec<Move>)> {
+        let cube = CubieCube::random(rng);
+        // Every cube can be solved in 20 face turns but it's much quicker to allow a few more,
+        // counted in the solver's metric at the most a face turn can cost
+        let max_cost = self.phase1_moves.iter().filter(|am| am.moves.len() == 1).map(|am| am.cost as usize).max().unwrap_or(1);
+        let length = SCRAMBLE_MOVES * max_cost;
+        let solution = self.solve_with(&cube, length, limits)?.solution.ok_or(Error::NoScramble(length))?;
+        let scramble = solution.moves.iter().rev().map(|m| m.inverse()).collect();
+        Ok((cube, scramble))
+    }
+
+    /// Find up to `limit` distinct solutions of at most `length` moves, sorted by length
+    /// Solutions are canonical: no face is turned twice in a row and opposite faces are
+    /// always turned in the order U before D, R before L and F before B
+    pub fn solve_all(&self, cube: &CubieCube, length: usize, limit: usize) -> Result<Vec<Solution>> {
+        Ok(self.search(cube, length, limit, false, &Limits::default(), &mut ())?.0)
+    }
+
+    /// Same as `solve_all` but only returns solutions of exactly `length` moves
+    pub fn solve_all_exact(&self, cube: &CubieCube, length: usize, limit: usize) -> Result<Vec<Solution>> {
+        Ok(self.search(cube, length, limit, true, &Limits::default(), &mut ())?.0)
+    }
+
+    /// Like `solve` but also says how long it took and how many nodes were searched
+    pub fn solve_report(&self, cube: &CubieCube, max_length: usize) -> Result<SolveReport> {
+        self.solve_with(cube, max_length, &Limits::default())
+    }
+
+    /// `solve_report` that gives up when it hits one of `limits`
+    pub fn solve_with(&self, cube: &CubieCube, max_length: usize, limits: &Limits) -> Result<SolveReport> {
+        self.solve_observed(cube, max_length, limits, &mut ())
+    }
+
+    /// `solve_with` that tells `observer` what's happening as it goes
+    pub fn solve_observed(&self, cube: &CubieCube, max_length: usize, limits: &Limits, observer: &mut dyn SearchObserver) -> Result<SolveReport> {
+        let start = Clock::now();
+        let (mut solutions, stats) = self.search(cube, max_length, 1, false, limits, observer)?;
+        Ok(SolveReport { solution: solutions.pop(), elapsed: start.elapsed(), stats })
+    }
+
+    fn search<'a>(&'a self, cube: &'a CubieCube, length: usize, limit: usize, exact: bool, limits: &Limits<'a>, observer: &'a mut dyn SearchObserver) -> Result<(Vec<Solution>, SearchStats)> {
+        // The coordinates and packed cube assume a real cube, anything else can loop forever
+        cube.verify()?;
+        self.check_reachable(cube)?;
+        let start = Clock::now();
+
+        let mut search = Search {
+            solver: self,
+            cube: PackedCube::from(cube),
+            length,
+            limit,
+            exact,
+            path: Vec::with_capacity(length),
+            cost: 0,
+            solutions: Vec::new(),
+            stats: SearchStats::default(),
+            observer,
+            #[cfg(feature = "std")]
+            // Too far off to represent is the same as no deadline
+            deadline: limits.timeout.and_then(|t| Instant::now().checked_add(t)),
+            cancel: limits.cancel,
+            stopped: None,
+        };
+
+        let twist = cube.get_twist() as usize;
+        let flip = cube.get_flip() as usize;
+        let slice_sorted = cube.get_slice_sorted() as usize;
+
+        // Every phase 1 length, each solution is found at exactly one of them
+        for depth in self.phase1_dist(twist, flip, slice_sorted)..=length {
+            search.observer.phase1_depth(depth, &search.stats);
+            search.phase1(twist, flip, slice_sorted, depth, None);
+            if search.done() {
+                break;
+            }
+        }
+
+        let mut stats = search.stats;
+        stats.phase1_elapsed = start.elapsed().saturating_sub(stats.phase2_elapsed);
+
+        // Anything found before stopping is still good
+        let mut solutions = search.solutions;
+        if let Some(e) = search.stopped {
+            search.observer.stopped(&e, &stats);
+            if solutions.is_empty() {
+                return Err(e);
+            }
+        }
+        solutions.sort_by_key(|s| s.cost);
+        Ok((solutions, stats))
+    }
+
+    /// Lower bound on the moves needed to get into the phase 2 subgroup
+    fn phase1_dist(&self, twist: usize, flip: usize, slice_sorted: usize) -> usize {
+        let slice = slice_sorted / N_PERM_4;
+        match &self.phase1_prun {
+            Phase1Prun::TwistFlip { twist_slice, flip_slice } => {
+                twist_slice[N_SLICE*twist + slice].max(flip_slice[N_SLICE*flip + slice]) as usize
+            }
+            Phase1Prun::Symmetric { symmetries, table } => get_nibble(table, symmetries.index(twist, flip, slice)) as usize,
+        }
+    }
+
+    /// Lower bound on the moves needed to solve a phase 2 cube
+    fn phase2_dist(&self, corners: usize, ud_edges: usize, slice_sorted: usize) -> usize {
+        match &self.phase2_prun {
+            Phase2Prun::Slice { corners_slice, ud_edges_slice } => {
+                corners_slice[N_PERM_4*corners + slice_sorted].max(ud_edges_slice[N_PERM_4*ud_edges + slice_sorted]) as usize
+            }
+            Phase2Prun::Small { corners: c, ud_edges: u } => c[corners].max(u[ud_edges]) as usize,
+        }
+    }
+}
+
+/// State of a single search
+struct Search<'a> {
+    solver: &'a Solver,
+    // Packed since phase 2 is started from it for every phase 1 solution
+    cube: PackedCube,
+    length: usize,
+    limit: usize,
+    exact: bool,
+    path: Vec<Move>,
+    cost: usize,
+    solutions: Vec<Solution>,
+    stats: SearchStats,
+    observer: &'a mut dyn SearchObserver,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+    cancel: Option<&'a AtomicBool>,
+    // Timeout or Cancelled once a limit is hit
+    stopped: Option<Error>,
+}
+
+impl<'a> Search<'a> {
+    fn done(&self) -> bool {
+        self.solutions.len() >= self.limit || self.stopped.is_some()
+    }
+
+    /// Checks the limits every so often, call after counting a node
+    fn visit(&mut self) {
+        if self.stats.nodes() % 1024 != 1 {
+            return;
+        }
+        if self.cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+            self.stopped = Some(Error::Cancelled);
+        } else if self.timed_out() {
+            self.stopped = Some(Error::Timeout);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn timed_out(&self) -> bool {
+        self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+
+    // No clock to time out with
+    #[cfg(not(feature = "std"))]
+    fn timed_out(&self) -> bool {
+        false
+    }
+
+    /// Depth first search for phase 1 sequences costing exactly `togo` more
+    fn phase1(&mut self, twist: usize, flip: usize, slice_sorted: usize, togo: usize, last: Option<&'a AxisMove>) {
+        if self.done() {
+            return;
+        }
+        self.stats.phase1_nodes += 1;
+        self.visit();
+
+        if togo == 0 {
+            // Phase 1 solutions ending in a phase 2 move are found by phase 2 from a shorter one
+            if !last.is_some_and(|am| am.is_phase2()) {
+                self.start_phase2(last);
+            }
+            return;
+        }
+
+        let s = self.solver;
+        for am in &s.phase1_moves {
+            // Turns on the same axis are all done in one axis move
+            if am.cost as usize > togo || last.is_some_and(|l| l.axis() == am.axis()) {
+                continue;
+            }
+
+            let (mut twist1, mut flip1, mut slice_sorted1) = (twist, flip, slice_sorted);
+            for &m in &am.moves {
+                twist1 = s.twist_move[N_MOVE*twist1 + m] as usize;
+                flip1 = s.flip_move[N_MOVE*flip1 + m] as usize;
+                slice_sorted1 = s.slice_sorted_move[N_MOVE*slice_sorted1 + m] as usize;
+            }
+            let togo1 = togo - am.cost as usize;
+            self.stats.move_lookups += 3*am.moves.len() as u64;
+            self.stats.prune_lookups += 2;
+            if s.phase1_dist(twist1, flip1, slice_sorted1) > togo1 {
+                self.stats.prune(self.path.len());
+                continue;
+            }
+
+            self.push(am);
+            self.phase1(twist1, flip1, slice_sorted1, togo1, Some(am));
+            self.pop(am);
+        }
+    }
+
+    /// Set up the phase 2 coordinates by applying the phase 1 moves to the cube
+    fn start_phase2(&mut self, last: Option<&'a AxisMove>) {
+        let start = Clock::now();
+        self.stats.phase1_solutions += 1;
+        self.observer.phase1_solution(&self.path);
+
+        let mut cube = self.cube;
+        cube.apply_moves(&self.path);
+
+        let corners = cube.get_corners() as usize;
+        let ud_edges = cube.get_ud_edges() as usize;
+        let slice_sorted = cube.get_slice_sorted() as usize;
+
+        let togo = self.length - self.cost;
+        self.stats.prune_lookups += 2;
+        if self.solver.phase2_dist(corners, ud_edges, slice_sorted) <= togo {
+            self.phase2(corners, ud_edges, slice_sorted, togo, last);
+        } else {
+            self.stats.prune(self.path.len());
+        }
+        self.stats.phase2_elapsed += start.elapsed();
+    }
+
+    /// Depth first search for phase 2 sequences costing up to `togo` more
+    fn phase2(&mut self, corners: usize, ud_edges: usize, slice_sorted: usize, togo: usize, last: Option<&'a AxisMove>) {
+        if self.done() {
+            return;
+        }
+        self.stats.phase2_nodes += 1;
+        self.visit();
+
+        if corners == 0 && ud_edges == 0 && slice_sorted == 0 && (togo == 0 || !self.exact) {
+            let solution = Solution { moves: self.path.clone(), cost: self.cost };
+            self.observer.solution(&solution, &self.stats);
+            self.solutions.push(solution);
+        }
+        if togo == 0 {
+            return;
+        }
+
+        let s = self.solver;
+        for am in &s.phase2_moves {
+            if am.cost as usize > togo || last.is_some_and(|l| l.axis() == am.axis()) {
+                continue;
+            }
+
+            let (mut corners1, mut ud_edges1, mut slice_sorted1) = (corners, ud_edges, slice_sorted);
+            for &m in &am.moves {
+                corners1 = s.corners_move[N_MOVE*corners1 + m] as usize;
+                ud_edges1 = s.ud_edges_move[N_MOVE*ud_edges1 + m] as usize;
+                slice_sorted1 = s.slice_sorted_move[N_MOVE*slice_sorted1 + m] as usize;
+            }
+            let togo1 = togo - am.cost as usize;
+            self.stats.move_lookups += 3*am.moves.len() as u64;
+            self.stats.prune_lookups += 2;
+            if s.phase2_dist(corners1, ud_edges1, slice_sorted1) > togo1 {
+                self.stats.prune(self.path.len());
+                continue;
+            }
+
+            self.push(am);
+            self.phase2(corners1, ud_edges1, slice_sorted1, togo1, Some(am));
+            self.pop(am);
+        }
+    }
+
+    fn push(&mut self, am: &AxisMove) {
+        self.path.extend(am.moves.iter().map(|&m| Move::from_index(m)));
+        self.cost += am.cost as usize;
+    }
+
+    fn pop(&mut self, am: &AxisMove) {
+        self.path.truncate(self.path.len() - am.moves.len());
+        self.cost -= am.cost as usize;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common::Corner, cubie::CubieError, pattern::Pattern, test_tables::{self, solver}};
+
+    /// True if `m` can follow `last` in a canonical sequence
+    fn is_canonical(last: Option<Move>, m: Move) -> bool {
+        match last {
+            None => true,
+            Some(l) => {
+                let (lf, f) = (l.face(), m.face());
+                // Same face, or the opposite face in the wrong order
+                !(lf == f || (lf % 3 == f % 3 && f < lf))
+            }
+        }
+    }
+
+    fn scrambled(moves: &[Move]) -> CubieCube {
+        let mut cube = CubieCube::default();
+        cube.apply_moves(moves);
+        cube
+    }
+
+    fn check(cube: &CubieCube, solution: &Solution) {
+        let mut c = cube.clone();
+        c.apply_moves(&solution.moves);
+        assert!(c.is_solved(), "{} doesn't solve the cube", solution);
+    }
+
+    #[test]
+    fn solved_cube_has_empty_solution() {
+        let solutions = solver().solve_all(&CubieCube::default(), 2, 10).unwrap();
+        assert_eq!(solutions, vec![Solution { moves: vec![], cost: 0 }]);
+    }
+
+    #[test]
+    fn solves_scramble() {
+        use Move::*;
+        let cube = scrambled(&[R1, U1, F2, L3, D1, B2, R3, F1, U2, L1]);
+        let solution = solver().solve(&cube, 20).unwrap().unwrap();
+        assert!(solution.len() <= 20);
+        check(&cube, &solution);
+    }
+
+    #[test]
+    fn report_counts_nodes() {
+        let cube = scrambled(&[Move::R1, Move::U1, Move::F3]);
+        let report = solver().solve_report(&cube, 20).unwrap();
+        check(&cube, &report.solution.unwrap());
+        let stats = report.stats;
+        assert!(stats.phase1_nodes > 0 && stats.phase2_nodes > 0);
+        assert!(stats.phase1_solutions > 0);
+        assert!(stats.move_lookups > 0 && stats.prune_lookups > 0);
+        assert!(stats.pruned.iter().sum::<u64>() > 0);
+        assert!(stats.phase1_elapsed + stats.phase2_elapsed <= report.elapsed);
+    }
+
+    fn tables() -> SolverTables {
+        let s = solver();
+        let (Phase1Prun::TwistFlip { twist_slice, flip_slice }, Phase2Prun::Slice { corners_slice, ud_edges_slice }) = (&s.phase1_prun, &s.phase2_prun) else {
+            unreachable!()
+        };
+        SolverTables {
+            twist_move: &s.twist_move,
+            flip_move: &s.flip_move,
+            slice_sorted_move: &s.slice_sorted_move,
+            corners_move: &s.corners_move,
+            ud_edges_move: &s.ud_edges_move,
+            twist_slice_prun: twist_slice,
+            flip_slice_prun: flip_slice,
+            corners_slice_prun: corners_slice,
+            ud_edges_slice_prun: ud_edges_slice,
+        }
+    }
+
+    #[test]
+    fn from_tables_solves() {
+        use Move::*;
+        let solver = Solver::from_tables(tables(), Metric::Half).unwrap();
+        let cube = scrambled(&[R1, U1, F2, L3, D1, B2, R3, F1, U2, L1]);
+        let solution = solver.solve(&cube, 20).unwrap().unwrap();
+        check(&cube, &solution);
+    }
+
+    #[test]
+    fn from_tables_rejects_bad_tables() {
+        let mut bad = tables();
+        bad.flip_move = &bad.flip_move[1..];
+        assert!(matches!(Solver::from_tables(bad, Metric::Half), Err(Error::InvalidTable("flip_move"))));
+
+        static OUT_OF_RANGE: [u16; N_SLICE_SORTED * N_MOVE] = [u16::MAX; N_SLICE_SORTED * N_MOVE];
+        let mut bad = tables();
+        bad.slice_sorted_move = &OUT_OF_RANGE;
+        assert!(matches!(Solver::from_tables(bad, Metric::Half), Err(Error::InvalidTable("slice_sorted_move"))));
+
+        let mut bad = tables();
+        bad.corners_slice_prun = &[];
+        assert!(matches!(Solver::from_tables(bad, Metric::Half), Err(Error::InvalidTable("corners_slice_prun"))));
+    }
+
+    #[test]
+    fn table_sets() {
+        use Move::*;
+        let all: Vec<Move> = Move::iter().collect();
+        assert_eq!(solver().table_set(), TableSet::TwistFlip);
+        assert_eq!(solver().table_bytes(), TableSet::TwistFlip.bytes(&all));
+        assert_eq!(solver().table_summary(), "twist-flip tables, 7.2 MB, 1x the solve time of twist-flip");
+
+        let small = Solver::with_budget(Some(test_tables::dir()), &all, Metric::Half, 6 << 20).unwrap();
+        assert_eq!(small.table_set(), TableSet::Small);
+        assert_eq!(small.table_bytes(), TableSet::Small.bytes(&all));
+        let cube = scrambled(&[R1, U1, F2, L3, D1, B2, R3, F1, U2, L1]);
+        check(&cube, &small.solve(&cube, 20).unwrap().unwrap());
+
+        // Not for a restricted move set
+        let two_gen = [R1, R2, R3, U1, U2, U3];
+        let solver = Solver::with_table_set(Some(test_tables::dir()), &two_gen, Metric::Half, TableSet::Symmetric).unwrap();
+        assert_eq!(solver.table_set(), TableSet::TwistFlip);
+        assert_eq!(solver.table_bytes(), TableSet::TwistFlip.bytes(&two_gen));
+
+        assert!(matches!(Solver::with_budget(Some(test_tables::dir()), &all, Metric::Half, 1 << 20), Err(Error::OverBudget { .. })));
+    }
+
+    #[test]
+    fn symmetric_tables() {
+        use rand::SeedableRng;
+        let all: Vec<Move> = Move::iter().collect();
+        // The 70 MB table takes a few minutes in a debug build the first time, after that it's
+        // loaded from the shared directory
+        let symmetric = Solver::with_table_set(Some(test_tables::dir()), &all, Metric::Half, TableSet::Symmetric).unwrap();
+        assert_eq!(symmetric.table_set(), TableSet::Symmetric);
+        assert_eq!(symmetric.table_bytes(), TableSet::Symmetric.bytes(&all));
+
+        // Exact phase 1 distances, so never less than the twist and flip tables say
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        for _ in 0..1000 {
+            let cube = CubieCube::random(&mut rng);
+            let (twist, flip, slice_sorted) = (cube.get_twist() as usize, cube.get_flip() as usize, cube.get_slice_sorted() as usize);
+            assert!(symmetric.phase1_dist(twist, flip, slice_sorted) >= solver().phase1_dist(twist, flip, slice_sorted));
+        }
+        for _ in 0..2 {
+            let cube = CubieCube::random(&mut rng);
+            check(&cube, &symmetric.solve(&cube, 20).unwrap().unwrap());
+        }
+    }
+
+    #[test]
+    fn invalid_cube() {
+        let twisted = CubieCube::new(None, Some([1, 0, 0, 0, 0, 0, 0, 0]), None, None);
+        let limits = Limits::default();
+        assert!(matches!(solver().solve(&twisted, 20), Err(Error::InvalidCube(CubieError::Twist))));
+        assert!(matches!(solver().solve_all(&twisted, 20, 10), Err(Error::InvalidCube(_))));
+        assert!(matches!(solver().solve_with(&twisted, 20, &limits), Err(Error::InvalidCube(_))));
+        assert!(matches!(solver().solve_observed(&twisted, 20, &limits, &mut ()), Err(Error::InvalidCube(_))));
+        assert!(matches!(solver().solve_to(&twisted, &CubieCube::default(), 20), Err(Error::InvalidCube(_))));
+
+        // Would never get past the corner coordinate
+        let swapped = CubieCube::new(Some([Corner::UFR; 8]), None, None, None);
+        assert!(matches!(solver().solve(&swapped, 20), Err(Error::InvalidCube(CubieError::CornerPermutation))));
+    }
+
+    #[test]
+    fn observer_sees_events() {
+        #[derive(Default)]
+        struct Events {
+            depths: Vec<usize>,
+            phase1_solutions: u64,
+            solutions: Vec<Solution>,
+            stopped: Option<String>,
+        }
+
+        impl SearchObserver for Events {
+            fn phase1_depth(&mut self, depth: usize, _stats: &SearchStats) {
+                self.depths.push(depth);
+            }
+            fn phase1_solution(&mut self, _moves: &[Move]) {
+                self.phase1_solutions += 1;
+            }
+            fn solution(&mut self, solution: &Solution, _stats: &SearchStats) {
+                self.solutions.push(solution.clone());
+            }
+            fn stopped(&mut self, reason: &Error, _stats: &SearchStats) {
+                self.stopped = Some(reason.to_string());
+            }
+        }
+
+        let cube = scrambled(&[Move::R1, Move::U1, Move::F3, Move::D2]);
+        let mut events = Events::default();
+        let report = solver().solve_observed(&cube, 20, &Limits::default(), &mut events).unwrap();
+        assert!(!events.depths.is_empty() && events.depths.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(events.phase1_solutions, report.stats.phase1_solutions);
+        assert_eq!(events.solutions, vec![report.solution.unwrap()]);
+        assert!(events.stopped.is_none());
+
+        let mut events = Events::default();
+        let limits = Limits { timeout: Some(Duration::ZERO), cancel: None };
+        assert!(solver().solve_observed(&Pattern::Superflip.cube(), 20, &limits, &mut events).is_err());
+        assert!(events.stopped.is_some());
+    }
+
+    #[test]
+    fn scramble_makes_the_cube() {
+        let (cube, moves) = solver().scramble(&mut rand::thread_rng(), &Limits::default()).unwrap();
+        assert!(scrambled(&moves) == cube);
+
+        let limits = Limits { timeout: None, cancel: Some(&AtomicBool::new(true)) };
+        assert!(matches!(solver().scramble(&mut rand::thread_rng(), &limits), Err(Error::Cancelled)));
+    }
+
+    #[test]
+    fn timeout_and_cancel() {
+        let cube = Pattern::Superflip.cube();
+        let limits = Limits { timeout: Some(Duration::ZERO), cancel: None };
+        assert!(matches!(solver().solve_with(&cube, 20, &limits), Err(Error::Timeout)));
+
+        let cancel = AtomicBool::new(true);
+        let limits = Limits { timeout: None, cancel: Some(&cancel) };
+        assert!(matches!(solver().solve_with(&cube, 20, &limits), Err(Error::Cancelled)));
+
+        // Plenty of time
+        let limits = Limits { timeout: Some(Duration::from_secs(60)), cancel: None };
+        check(&cube, &solver().solve_with(&cube, 24, &limits).unwrap().solution.unwrap());
+        let limits = Limits { timeout: Some(Duration::MAX), cancel: None };
+        check(&cube, &solver().solve_with(&cube, 24, &limits).unwrap().solution.unwrap());
+    }
+
+    #[test]
+    fn solve_all_distinct_and_canonical() {
+        use Move::*;
+        let cube = scrambled(&[R1, U1, F3]);
+        let solutions = solver().solve_all(&cube, 7, 50).unwrap();
+        assert!(!solutions.is_empty());
+        assert_eq!(solutions[0].moves, vec![F1, U3, R3]);
+
+        for (i, s) in solutions.iter().enumerate() {
+            check(&cube, s);
+            assert!(s.len() <= 7);
+            for w in s.moves.windows(2) {
+                assert!(is_canonical(Some(w[0]), w[1]), "{} isn't canonical", s);
+            }
+            assert!(!solutions[i + 1..].contains(s), "{} found twice", s);
+        }
+    }
+
+    #[test]
+    fn solve_all_exact_length() {
+        use Move::*;
+        let cube = scrambled(&[R1, U1]);
+        let solutions = solver().solve_all_exact(&cube, 8, 100).unwrap();
+        assert!(!solutions.is_empty());
+        for s in &solutions {
+            assert_eq!(s.len(), 8);
+            check(&cube, s);
+        }
+
+        // Same as filtering everything up to that length
+        let all: Vec<Solution> = solver().solve_all(&cube, 8, 100).unwrap().into_iter().filter(|s| s.len() == 8).collect();
+        assert_eq!(all.len(), solutions.len());
+        assert!(solutions.iter().all(|s| all.contains(s)));
+    }
+
+    #[test]
+    fn solve_all_counts_opposite_faces_once() {
+        use Move::*;
+        // U D and D U are the same thing, only the canonical one is returned
+        let cube = scrambled(&[U1, D1]);
+        let solutions = solver().solve_all(&cube, 2, 10).unwrap();
+        assert_eq!(solutions, vec![Solution { moves: vec![U3, D3], cost: 2 }]);
+    }
+
+    #[test]
+    fn two_gen_solution() {
+        use Move::*;
+        let solver = Solver::with_moves(Some(test_tables::dir()), &[R1, R2, R3, U1, U2, U3]).unwrap();
+        let cube = scrambled(&[R1, U1, R3, U1, R1, U2, R3]);
+        let solution = solver.solve(&cube, 10).unwrap().unwrap();
+        check(&cube, &solution);
+        assert!(solution.moves.iter().all(|m| m.face() == 0 || m.face() == 1), "{} isn't <R, U>", solution);
+    }
+
+    #[test]
+    fn two_gen_unreachable() {
+        use Move::*;
+        let solver = Solver::with_moves(Some(test_tables::dir()), &[R1, R2, R3, U1, U2, U3]).unwrap();
+        assert!(matches!(solver.solve(&scrambled(&[F1]), 20), Err(Error::Unreachable)));
+        assert!(matches!(solver.solve(&scrambled(&[R1, L1]), 20), Err(Error::Unreachable)));
+    }
+
+    #[test]
+    fn passes_check_but_unreachable() {
+        use Move::*;
+        // <R2, U2> only has 12 states, so the corners of R2 with the edges of U2 isn't one of
+        // them even though every coordinate on its own is
+        let solver = Solver::with_moves(Some(test_tables::dir()), &[R2, U2]).unwrap();
+        let (r, u) = (scrambled(&[R2]), scrambled(&[U2]));
+        let cube = CubieCube { cp: r.cp, co: r.co, ep: u.ep, eo: u.eo };
+        assert!(solver.check_reachable(&cube).is_ok());
+        assert!(solver.solve(&cube, 20).unwrap().is_none());
+    }
+
+    #[test]
+    fn no_b_solution() {
+        use Move::*;
+        let moves: Vec<Move> = Move::iter().filter(|m| m.face() != 5).collect();
+        let solver = Solver::with_moves(Some(test_tables::dir()), &moves).unwrap();
+        let cube = scrambled(&[B1, R1, U2, B3, D1]);
+        let solution = solver.solve(&cube, 20).unwrap().unwrap();
+        check(&cube, &solution);
+        assert!(solution.moves.iter().all(|m| m.face() != 5), "{} turns B", solution);
+    }
+
+    #[test]
+    fn quarter_turn_metric() {
+        use Move::*;
+        let solver = Solver::with_metric(Some(test_tables::dir()), Metric::Quarter).unwrap();
+        let cube = scrambled(&[R2, U1, F3]);
+        let solutions = solver.solve_all(&cube, 5, 10).unwrap();
+        assert_eq!(solutions[0].moves, vec![F1, U3, R2]);
+        assert_eq!(solutions[0].cost, 4);
+        for s in &solutions {
+            check(&cube, s);
+            assert_eq!(s.cost, Metric::Quarter.cost(&s.moves));
+        }
+
+        // Random cubes can need more than 24 quarter turns
+        let (cube, moves) = solver.scramble(&mut rand::thread_rng(), &Limits::default()).unwrap();
+        assert!(scrambled(&moves) == cube);
+    }
+
+    #[test]
+    fn free_moves_rejected() {
+        use Move::*;
+        let mut costs = [1; N_MOVE];
+        costs[U1 as usize] = 0;
+        costs[R1 as usize] = 0;
+        let metric = Metric::Custom(costs);
+        assert!(matches!(Solver::with_metric(Some(test_tables::dir()), metric), Err(Error::InvalidMetric(U1))));
+        assert!(matches!(Solver::generate(&[R1, U1], metric), Err(Error::InvalidMetric(U1))));
+        assert!(matches!(Solver::from_tables(tables(), metric), Err(Error::InvalidMetric(U1))));
+    }
+
+    #[test]
+    fn slice_turn_metric() {
+        use Move::*;
+        let solver = Solver::with_metric(Some(test_tables::dir()), Metric::Slice).unwrap();
+        // M' U M is 3 slice moves but 5 face turns
+        let cube = scrambled(&[R1, L3, U1, R3, L1]);
+        let solution = solver.solve(&cube, 3).unwrap().unwrap();
+        check(&cube, &solution);
+        assert_eq!(solution.cost, 3);
+        assert_eq!(solution.len(), 5);
+    }
+
+    #[test]
+    fn solve_to_patterns() {
+        use Move::*;
+        let start = scrambled(&[R1, U1, F3, D2]);
+        for pattern in [Pattern::Checkerboard, Pattern::Superflip, Pattern::SixSpots] {
+            let goal = pattern.cube();
+            let solution = solver().solve_to(&start, &goal, 24).unwrap().unwrap();
+            let mut c = start.clone();
+            c.apply_moves(&solution.moves);
+            assert!(c == goal, "{} doesn't reach {:?}", solution, pattern);
+        }
+    }
+}
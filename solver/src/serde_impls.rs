@@ -0,0 +1,244 @@
+// Serialize/Deserialize for the public types, behind the `serde` feature
+// Everything is written the way a person would, facelet strings and move notation
+
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    common::Move,
+    cubie::CubieCube,
+    face::FaceCube,
+    notation::{parse_face_moves, ExtMove},
+    search::{SearchStats, SolveReport, Solution},
+};
+
+fn serialize_display<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+fn deserialize_from_str<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(D::Error::custom)
+}
+
+/// The 54 letter facelet string, `UUUUUUUUURRR...`
+impl Serialize for FaceCube {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:?}", self))
+    }
+}
+
+impl<'de> Deserialize<'de> for FaceCube {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CubieRepr {
+    cp: [u8; 8],
+    co: [u8; 8],
+    ep: [u8; 12],
+    eo: [u8; 12],
+}
+
+/// The four arrays, checked with `CubieCube::verify` when read
+impl Serialize for CubieCube {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CubieRepr {
+            cp: self.cp.map(|c| c as u8),
+            co: self.co.map(|o| o as u8),
+            ep: self.ep.map(|e| e as u8),
+            eo: self.eo.map(|o| o as u8),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CubieCube {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let r = CubieRepr::deserialize(deserializer)?;
+        CubieCube::from_arrays(r.cp, r.co, r.ep, r.eo).map_err(D::Error::custom)
+    }
+}
+
+/// Notation, `R2`
+impl Serialize for Move {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_display(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Move {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
+/// Notation, `Rw'` or `x2`
+impl Serialize for ExtMove {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_display(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExtMove {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SolutionRepr {
+    moves: String,
+    length: usize,
+    cost: usize,
+}
+
+/// `{"moves": "R U2 F'", "length": 3, "cost": 3}`
+impl Serialize for Solution {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SolutionRepr { moves: self.to_string(), length: self.len(), cost: self.cost }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Solution {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let r = SolutionRepr::deserialize(deserializer)?;
+        let moves = parse_face_moves(&r.moves).map_err(D::Error::custom)?;
+        if moves.len() != r.length {
+            return Err(D::Error::custom(format!("{} moves but length is {}", moves.len(), r.length)));
+        }
+        Ok(Solution { moves, cost: r.cost })
+    }
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64()*1000.0
+}
+
+fn from_millis<E: Error>(ms: f64) -> Result<Duration, E> {
+    Duration::try_from_secs_f64(ms/1000.0).map_err(E::custom)
+}
+
+#[derive(Serialize, Deserialize)]
+struct SearchStatsRepr {
+    phase1_nodes: u64,
+    phase2_nodes: u64,
+    phase1_solutions: u64,
+    move_lookups: u64,
+    prune_lookups: u64,
+    pruned: Vec<u64>,
+    phase1_ms: f64,
+    phase2_ms: f64,
+}
+
+/// The counters as they are, with the times in milliseconds
+impl Serialize for SearchStats {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SearchStatsRepr {
+            phase1_nodes: self.phase1_nodes,
+            phase2_nodes: self.phase2_nodes,
+            phase1_solutions: self.phase1_solutions,
+            move_lookups: self.move_lookups,
+            prune_lookups: self.prune_lookups,
+            pruned: self.pruned.clone(),
+            phase1_ms: millis(self.phase1_elapsed),
+            phase2_ms: millis(self.phase2_elapsed),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SearchStats {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let r = SearchStatsRepr::deserialize(deserializer)?;
+        Ok(SearchStats {
+            phase1_nodes: r.phase1_nodes,
+            phase2_nodes: r.phase2_nodes,
+            phase1_solutions: r.phase1_solutions,
+            move_lookups: r.move_lookups,
+            prune_lookups: r.prune_lookups,
+            pruned: r.pruned,
+            phase1_elapsed: from_millis(r.phase1_ms)?,
+            phase2_elapsed: from_millis(r.phase2_ms)?,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SolveReportRepr {
+    solution: Option<Solution>,
+    elapsed_ms: f64,
+    stats: SearchStats,
+}
+
+/// Solution (or null) with the time in milliseconds and what the search did
+impl Serialize for SolveReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SolveReportRepr {
+            solution: self.solution.clone(),
+            elapsed_ms: millis(self.elapsed),
+            stats: self.stats.clone(),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SolveReport {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let r = SolveReportRepr::deserialize(deserializer)?;
+        Ok(SolveReport { solution: r.solution, elapsed: from_millis(r.elapsed_ms)?, stats: r.stats })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Move::*;
+
+    #[test]
+    fn face_cube_is_a_string() {
+        let json = serde_json::to_string(&FaceCube::new()).unwrap();
+        assert_eq!(json, format!("\"{:?}\"", FaceCube::new()));
+        assert!(serde_json::from_str::<FaceCube>(&json).unwrap() == FaceCube::new());
+        assert!(serde_json::from_str::<FaceCube>("\"UUX\"").is_err());
+    }
+
+    #[test]
+    fn cubie_cube_arrays() {
+        let mut cube = CubieCube::default();
+        cube.apply_moves(&[R1, U1, F3]);
+        let json = serde_json::to_string(&cube).unwrap();
+        assert!(json.starts_with("{\"cp\":["));
+        assert!(serde_json::from_str::<CubieCube>(&json).unwrap() == cube);
+
+        // Single twisted corner
+        let bad = r#"{"cp":[0,1,2,3,4,5,6,7],"co":[1,0,0,0,0,0,0,0],"ep":[0,1,2,3,4,5,6,7,8,9,10,11],"eo":[0,0,0,0,0,0,0,0,0,0,0,0]}"#;
+        assert!(serde_json::from_str::<CubieCube>(bad).is_err());
+    }
+
+    #[test]
+    fn moves_and_solutions() {
+        assert_eq!(serde_json::to_string(&vec![R1, U2, F3]).unwrap(), r#"["R","U2","F'"]"#);
+        assert_eq!(serde_json::from_str::<Vec<ExtMove>>(r#"["Rw","x'"]"#).unwrap().len(), 2);
+
+        let solution = Solution { moves: vec![R1, U2, F3], cost: 3 };
+        let json = serde_json::to_string(&solution).unwrap();
+        assert_eq!(json, r#"{"moves":"R U2 F'","length":3,"cost":3}"#);
+        assert_eq!(serde_json::from_str::<Solution>(&json).unwrap(), solution);
+
+        let stats = SearchStats { phase1_nodes: 7, phase2_nodes: 3, pruned: vec![0, 4], ..Default::default() };
+        let report = SolveReport { solution: None, elapsed: Duration::from_millis(5), stats };
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.starts_with(r#"{"solution":null,"elapsed_ms":5.0,"stats":{"phase1_nodes":7,"phase2_nodes":3,"#), "{}", json);
+        let back = serde_json::from_str::<SolveReport>(&json).unwrap();
+        assert_eq!(back.elapsed, report.elapsed);
+        assert_eq!(back.stats, report.stats);
+    }
+}
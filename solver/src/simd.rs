@@ -0,0 +1,120 @@
+// Packed cube multiplication with byte shuffles. Multiplying permutations is looking up each byte
+// of `a` by a byte of `b`, which is exactly what SSSE3's pshufb does for 16 bytes at once.
+// The corners and edges each fit in one 128 bit register so AVX2 wouldn't add anything.
+// Checked for at runtime, anything without it uses the plain version in `packed`. Without std
+// there's no runtime check so it's only used if the target is built with ssse3
+
+use crate::packed;
+
+/// True if the shuffle versions are used on this machine
+pub fn available() -> bool {
+    #[cfg(all(target_arch = "x86_64", feature = "std"))]
+    {
+        std::arch::is_x86_feature_detected!("ssse3")
+    }
+    #[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+    {
+        cfg!(target_feature = "ssse3")
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+/// Corner bytes of `a * b`, see `PackedCube`
+pub fn corner_product(a: u64, b: u64) -> u64 {
+    #[cfg(target_arch = "x86_64")]
+    if available() {
+        // Safety: ssse3 was checked for above
+        return unsafe { x86::corner_product(a, b) };
+    }
+    packed::corner_product(a, b)
+}
+
+/// Edge bytes of `a * b`, see `PackedCube`
+pub fn edge_product(a: u128, b: u128) -> u128 {
+    #[cfg(target_arch = "x86_64")]
+    if available() {
+        // Safety: ssse3 was checked for above
+        return unsafe { x86::edge_product(a, b) };
+    }
+    packed::edge_product(a, b)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use core::arch::x86_64::*;
+
+    // Orientation sums 0..=4 (in the high nibble) mod 3, shifted back up to the high nibble
+    const MOD_3: [u8; 16] = [0x00, 0x10, 0x20, 0x00, 0x10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+    #[target_feature(enable = "ssse3")]
+    pub(super) unsafe fn corner_product(a: u64, b: u64) -> u64 {
+        let a = _mm_cvtsi64_si128(a as i64);
+        let b = _mm_cvtsi64_si128(b as i64);
+        let low = _mm_set1_epi8(0x0f);
+        let high = _mm_set1_epi8(0xf0u8 as i8);
+
+        // a[b[i]], the top 8 bytes of b are 0 so pick up a[0] but they're thrown away
+        let x = _mm_shuffle_epi8(a, _mm_and_si128(b, low));
+        // Add the orientations, then shift down to look up the sum mod 3
+        let sum = _mm_add_epi8(_mm_and_si128(x, high), _mm_and_si128(b, high));
+        let sum = _mm_and_si128(_mm_srli_epi16(sum, 4), low);
+        let ori = _mm_shuffle_epi8(_mm_loadu_si128(MOD_3.as_ptr() as *const __m128i), sum);
+
+        _mm_cvtsi128_si64(_mm_or_si128(_mm_and_si128(x, low), ori)) as u64
+    }
+
+    #[target_feature(enable = "ssse3")]
+    pub(super) unsafe fn edge_product(a: u128, b: u128) -> u128 {
+        let a = _mm_loadu_si128(&a as *const u128 as *const __m128i);
+        let b = _mm_loadu_si128(&b as *const u128 as *const __m128i);
+        let low = _mm_set1_epi8(0x0f);
+        let high = _mm_set1_epi8(0xf0u8 as i8);
+        // Only 12 edges, the rest have to stay 0
+        let edges = _mm_set_epi32(0, -1, -1, -1);
+
+        let x = _mm_shuffle_epi8(a, _mm_and_si128(b, low));
+        // Orientation is one bit so adding mod 2 is xor
+        let c = _mm_and_si128(_mm_xor_si128(x, _mm_and_si128(b, high)), edges);
+
+        let mut out = 0u128;
+        _mm_storeu_si128(&mut out as *mut u128 as *mut __m128i, c);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cubie::CubieCube, packed::PackedCube, strategies};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn prop_corners(a in strategies::cube(), b in strategies::cube()) {
+            let (pa, pb) = (PackedCube::from(&a), PackedCube::from(&b));
+            let mut expected = a.clone();
+            expected.corner_multiply_simple(&b);
+            prop_assert_eq!(corner_product(pa.corners, pb.corners), PackedCube::from(&expected).corners);
+        }
+
+        #[test]
+        fn prop_edges(a in strategies::cube(), b in strategies::cube()) {
+            let (pa, pb) = (PackedCube::from(&a), PackedCube::from(&b));
+            let mut expected = a.clone();
+            expected.edge_multiply(&b);
+            prop_assert_eq!(edge_product(pa.edges, pb.edges), PackedCube::from(&expected).edges);
+        }
+
+        /// Twisted and flipped as far as they go, past what a real cube can be
+        #[test]
+        fn prop_any_orientation(a in strategies::cube(), co in prop::array::uniform8(0..3i8), eo in prop::array::uniform12(0..2i8)) {
+            let b = CubieCube::new(Some(a.cp), Some(co), Some(a.ep), Some(eo));
+            let (pa, pb) = (PackedCube::from(&a), PackedCube::from(&b));
+            prop_assert_eq!(corner_product(pa.corners, pb.corners), packed::corner_product(pa.corners, pb.corners));
+            prop_assert_eq!(edge_product(pa.edges, pb.edges), packed::edge_product(pa.edges, pb.edges));
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::{
+    cubie::CubieCube,
+    error::Result,
+    search::{Limits, SearchStats, SolveReport, Solver},
+};
+
+/// What solving a set of cubes took, for spotting regressions and picking limits
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    /// Number of cubes with each solution length, in face turns
+    pub lengths: BTreeMap<usize, usize>,
+    /// Time of every search that finished, shortest first
+    pub times: Vec<Duration>,
+    /// Searches with no solution within the max length
+    pub unsolved: usize,
+    /// Searches that hit a limit or failed
+    pub failed: usize,
+    /// Work done by every search that finished, added up
+    pub search: SearchStats,
+}
+
+impl Stats {
+    /// Solve `count` uniformly random cubes on `threads` threads
+    /// One thread gives the most repeatable times
+    pub fn random(solver: &Solver, count: usize, max_length: usize, limits: &Limits, threads: usize, rng: &mut impl Rng) -> Stats {
+        let cubes: Vec<CubieCube> = (0..count).map(|_| CubieCube::random(rng)).collect();
+        Stats::from_results(solver.solve_batch_on(&cubes, max_length, limits, threads))
+    }
+
+    pub fn from_results(results: impl IntoIterator<Item = Result<SolveReport>>) -> Stats {
+        let mut stats = Stats::default();
+        for result in results {
+            let Ok(report) = result else {
+                stats.failed += 1;
+                continue;
+            };
+            match &report.solution {
+                Some(s) => *stats.lengths.entry(s.len()).or_default() += 1,
+                None => stats.unsolved += 1,
+            }
+            stats.times.push(report.elapsed);
+            stats.search.add(&report.stats);
+        }
+        stats.times.sort();
+        stats
+    }
+
+    pub fn solved(&self) -> usize {
+        self.lengths.values().sum()
+    }
+
+    pub fn total(&self) -> usize {
+        self.times.len() + self.failed
+    }
+
+    pub fn mean_length(&self) -> Option<f64> {
+        let total: usize = self.lengths.iter().map(|(len, n)| len*n).sum();
+        (self.solved() > 0).then(|| total as f64 / self.solved() as f64)
+    }
+
+    pub fn mean_time(&self) -> Option<Duration> {
+        let n = self.times.len() as u32;
+        (n > 0).then(|| self.times.iter().sum::<Duration>() / n)
+    }
+
+    /// Time that `p` percent of the searches finished within, nearest rank
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        if self.times.is_empty() {
+            return None;
+        }
+        let rank = (p / 100.0 * self.times.len() as f64).ceil() as usize;
+        Some(self.times[rank.clamp(1, self.times.len()) - 1])
+    }
+
+    /// `total` per finished search
+    fn per_search(&self, total: u64) -> f64 {
+        total as f64 / self.times.len().max(1) as f64
+    }
+}
+
+/// Plain text report
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} cubes, {} solved, {} unsolved, {} failed", self.total(), self.solved(), self.unsolved, self.failed)?;
+        if self.solved() > 0 {
+            writeln!(f, "length  cubes")?;
+            for (len, n) in &self.lengths {
+                writeln!(f, "{:>6}  {:>5}  {:>5.1}%", len, n, 100.0 * *n as f64 / self.solved() as f64)?;
+            }
+            writeln!(f, "mean length {:.2}", self.mean_length().unwrap_or_default())?;
+        }
+
+        if let Some(mean) = self.mean_time() {
+            let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+            write!(f, "time ms: mean {:.2}", ms(mean))?;
+            for p in [50.0, 90.0, 99.0, 100.0] {
+                write!(f, ", p{} {:.2}", p, ms(self.percentile(p).unwrap_or_default()))?;
+            }
+            writeln!(f)?;
+            let s = &self.search;
+            writeln!(f, "per search: {:.0} phase 1 nodes, {:.0} phase 2 nodes, {:.0} phase 1 solutions, {:.0} move lookups, {:.0} pruning lookups",
+                self.per_search(s.phase1_nodes), self.per_search(s.phase2_nodes), self.per_search(s.phase1_solutions),
+                self.per_search(s.move_lookups), self.per_search(s.prune_lookups))?;
+            let total = s.phase1_elapsed + s.phase2_elapsed;
+            if !total.is_zero() {
+                writeln!(f, "time in phase 2: {:.1}%", 100.0 * s.phase2_elapsed.as_secs_f64() / total.as_secs_f64())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{error::Error, search::Solution, test_tables::solver};
+
+    fn report(length: Option<usize>, ms: u64) -> Result<SolveReport> {
+        Ok(SolveReport {
+            solution: length.map(|n| Solution { moves: vec![crate::common::Move::R1; n], cost: n }),
+            elapsed: Duration::from_millis(ms),
+            stats: SearchStats { phase1_nodes: 6, phase2_nodes: 4, pruned: vec![1, 2], ..Default::default() },
+        })
+    }
+
+    #[test]
+    fn summary() {
+        let stats = Stats::from_results([
+            report(Some(18), 4),
+            report(Some(19), 1),
+            report(Some(19), 3),
+            report(None, 2),
+            Err(Error::Timeout),
+        ]);
+        assert_eq!(stats.total(), 5);
+        assert_eq!(stats.solved(), 3);
+        assert_eq!((stats.unsolved, stats.failed), (1, 1));
+        assert_eq!(stats.lengths, BTreeMap::from([(18, 1), (19, 2)]));
+        assert_eq!(stats.mean_length(), Some(56.0 / 3.0));
+        assert_eq!(stats.mean_time(), Some(Duration::from_micros(2500)));
+        assert_eq!(stats.percentile(50.0), Some(Duration::from_millis(2)));
+        assert_eq!(stats.percentile(100.0), Some(Duration::from_millis(4)));
+        assert_eq!(stats.percentile(0.0), Some(Duration::from_millis(1)));
+        assert_eq!(stats.search.phase1_nodes, 24);
+        assert_eq!(stats.search.pruned, vec![4, 8]);
+        assert!(stats.to_string().contains("5 cubes, 3 solved, 1 unsolved, 1 failed"));
+
+        assert_eq!(Stats::default().percentile(50.0), None);
+    }
+
+    #[test]
+    fn random_cubes() {
+        let stats = Stats::random(solver(), 4, 24, &Limits::default(), 2, &mut StdRng::seed_from_u64(1));
+        assert_eq!(stats.solved(), 4);
+        assert!(stats.lengths.keys().all(|&len| len <= 24));
+        assert!(stats.search.phase1_nodes > 0 && stats.search.phase2_nodes > 0);
+    }
+}
@@ -0,0 +1,40 @@
+// Proptest strategies shared by the tests in the other modules
+
+use proptest::prelude::*;
+
+use crate::{common::{Move, N_MOVE}, cubie::CubieCube};
+
+/// Any face turn
+pub(crate) fn any_move() -> impl Strategy<Value = Move> {
+    (0..N_MOVE).prop_map(Move::from_index)
+}
+
+/// Sequences of up to `max` face turns
+pub(crate) fn moves(max: usize) -> impl Strategy<Value = Vec<Move>> {
+    prop::collection::vec(any_move(), 0..=max)
+}
+
+/// Any solvable cube, built straight from the pieces rather than by turning so every state can come up
+pub(crate) fn cube() -> impl Strategy<Value = CubieCube> {
+    let cp = Just((0..8).collect::<Vec<u8>>()).prop_shuffle();
+    let ep = Just((0..12).collect::<Vec<u8>>()).prop_shuffle();
+    let co = prop::collection::vec(0..3u8, 7);
+    let eo = prop::collection::vec(0..2u8, 11);
+
+    (cp, co, ep, eo).prop_map(|(cp, co, mut ep, eo)| {
+        // Same fix ups as `CubieCube::random`
+        if parity(&cp) != parity(&ep) {
+            ep.swap(0, 1);
+        }
+        let mut co: [u8; 8] = [co, vec![0]].concat().try_into().unwrap();
+        co[7] = (3 - co[..7].iter().sum::<u8>() % 3) % 3;
+        let mut eo: [u8; 12] = [eo, vec![0]].concat().try_into().unwrap();
+        eo[11] = eo[..11].iter().sum::<u8>() % 2;
+
+        CubieCube::from_arrays(cp.try_into().unwrap(), co, ep.try_into().unwrap(), eo).unwrap()
+    })
+}
+
+fn parity(perm: &[u8]) -> usize {
+    (0..perm.len()).flat_map(|i| (0..i).map(move |j| (i, j))).filter(|&(i, j)| perm[j] > perm[i]).count() % 2
+}
@@ -0,0 +1,200 @@
+use std::fmt::Write;
+
+use crate::{
+    common::{Color, CORNER_FACELET, EDGE_FACELET},
+    cubie::CubieCube,
+    face::FaceCube,
+    scheme::ColorScheme,
+};
+
+// Size of a sticker in pixels, and the gap around it
+const STICKER: f64 = 30.0;
+const GAP: f64 = 2.0;
+const BORDER: &str = "#000000";
+
+fn fill(scheme: &ColorScheme, c: Color) -> String {
+    let (r, g, b) = scheme.sticker(c).rgb();
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn header(width: f64, height: f64) -> String {
+    format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n", w = width, h = height)
+}
+
+fn rect(out: &mut String, x: f64, y: f64, w: f64, h: f64, fill: &str) {
+    writeln!(out, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"{}\"/>", x, y, w, h, fill, BORDER).unwrap();
+}
+
+fn polygon(out: &mut String, points: &[(f64, f64)], fill: &str) {
+    let points: Vec<String> = points.iter().map(|(x, y)| format!("{:.2},{:.2}", x, y)).collect();
+    writeln!(out, "<polygon points=\"{}\" fill=\"{}\" stroke=\"{}\"/>", points.join(" "), fill, BORDER).unwrap();
+}
+
+/// Unfolded net, same layout as `FaceCube`'s `Display`
+pub fn net(cube: &FaceCube, scheme: &ColorScheme) -> String {
+    let mut out = header(12.0*STICKER + 2.0*GAP, 9.0*STICKER + 2.0*GAP);
+    for (row, cells) in cube.net().iter().enumerate() {
+        for (col, cell) in cells.iter().enumerate() {
+            if let Some(c) = cell {
+                rect(&mut out, GAP + col as f64*STICKER, GAP + row as f64*STICKER, STICKER, STICKER, &fill(scheme, *c));
+            }
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// 3D view of the U, F and R faces
+pub fn isometric(cube: &FaceCube, scheme: &ColorScheme) -> String {
+    let (cos, sin) = (30f64.to_radians().cos(), 30f64.to_radians().sin());
+    let width = 6.0*cos*STICKER + 2.0*GAP;
+    let height = 6.0*STICKER + 2.0*GAP;
+    // x goes right, y up and z towards the viewer, the cube is 3 stickers across
+    let project = |x: f64, y: f64, z: f64| {
+        (width/2.0 + (x - z)*cos*STICKER, GAP + 3.0*STICKER - y*STICKER + (x + z)*sin*STICKER)
+    };
+
+    let mut out = header(width, height);
+    for face in [Color::U, Color::F, Color::R] {
+        // Position in the cube of (column, row) on the face, rows go down from the top/back
+        let point = |c: f64, r: f64| match face {
+            Color::U => project(c, 3.0, r),
+            Color::F => project(c, 3.0 - r, 3.0),
+            _ => project(3.0, 3.0 - r, 3.0 - c),
+        };
+        for i in 0..9 {
+            let (r, c) = ((i / 3) as f64, (i % 3) as f64);
+            let corners = [point(c, r), point(c + 1.0, r), point(c + 1.0, r + 1.0), point(c, r + 1.0)];
+            polygon(&mut out, &corners, &fill(scheme, cube.faces[9*face as usize + i]));
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Top view of the last layer, the U face with the top row of each side face around it, and
+/// arrows showing where each U layer piece has to go. Pieces from the D layer sitting in the U
+/// layer have nowhere to go on this diagram, so they don't get an arrow
+pub fn last_layer(cube: &CubieCube, scheme: &ColorScheme) -> String {
+    let faces = FaceCube::from_cubie(cube);
+    let strip = STICKER/3.0;
+    let size = 3.0*STICKER + 2.0*strip + 2.0*GAP;
+    let origin = GAP + strip;
+
+    let mut out = header(size, size);
+    out.push_str("<defs><marker id=\"head\" viewBox=\"0 0 10 10\" refX=\"5\" refY=\"5\" markerWidth=\"4\" markerHeight=\"4\" orient=\"auto-start-reverse\">\
+                  <path d=\"M 0 0 L 10 5 L 0 10 z\"/></marker></defs>\n");
+
+    for i in 0..9 {
+        let (r, c) = ((i / 3) as f64, (i % 3) as f64);
+        rect(&mut out, origin + c*STICKER, origin + r*STICKER, STICKER, STICKER, &fill(scheme, faces.faces[i]));
+    }
+
+    // Side stickers in the order they're drawn, B along the top from the left, R down the right,
+    // F along the bottom and L down the left
+    let sides = [(Color::B, [2, 1, 0]), (Color::R, [2, 1, 0]), (Color::F, [0, 1, 2]), (Color::L, [0, 1, 2])];
+    for (side, (face, order)) in sides.iter().enumerate() {
+        for (k, &i) in order.iter().enumerate() {
+            let c = faces.faces[9*(*face as usize) + i];
+            let along = origin + k as f64*STICKER;
+            let (x, y, w, h) = match side {
+                0 => (along, GAP, STICKER, strip),
+                1 => (origin + 3.0*STICKER, along, strip, STICKER),
+                2 => (along, origin + 3.0*STICKER, STICKER, strip),
+                _ => (GAP, along, strip, STICKER),
+            };
+            rect(&mut out, x, y, w, h, &fill(scheme, c));
+        }
+    }
+
+    // Each piece goes from where it is to its home, which is a U facelet of the same index
+    let centre = |facelet: usize| {
+        (origin + (facelet % 3) as f64*STICKER + STICKER/2.0, origin + (facelet / 3) as f64*STICKER + STICKER/2.0)
+    };
+    let mut arrows = Vec::new();
+    for p in 0..4 {
+        if (cube.cp[p] as usize) < 4 {
+            arrows.push((CORNER_FACELET[p][0] as usize, CORNER_FACELET[cube.cp[p] as usize][0] as usize));
+        }
+        if (cube.ep[p] as usize) < 4 {
+            arrows.push((EDGE_FACELET[p][0] as usize, EDGE_FACELET[cube.ep[p] as usize][0] as usize));
+        }
+    }
+    for &(from, to) in &arrows {
+        // A swap is one arrow with a head at both ends
+        let swap = arrows.contains(&(to, from));
+        if from == to || (swap && from > to) {
+            continue;
+        }
+        let ((x1, y1), (x2, y2)) = (centre(from), centre(to));
+        let start = if swap { " marker-start=\"url(#head)\"" } else { "" };
+        writeln!(out, "<line class=\"arrow\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"2\"{} marker-end=\"url(#head)\"/>",
+            x1, y1, x2, y2, BORDER, start).unwrap();
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Move::*;
+
+    #[test]
+    fn net_has_every_sticker() {
+        let svg = net(&FaceCube::new(), &ColorScheme::western());
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<rect").count(), 54);
+        assert_eq!(svg.matches("fill=\"#ffffff\"").count(), 9);
+    }
+
+    #[test]
+    fn isometric_shows_three_faces() {
+        let svg = isometric(&FaceCube::new(), &ColorScheme::western());
+        assert_eq!(svg.matches("<polygon").count(), 27);
+        // U, F and R are white, green and red
+        for colour in ["#ffffff", "#009b48", "#b71234"] {
+            assert_eq!(svg.matches(colour).count(), 9);
+        }
+    }
+
+    #[test]
+    fn last_layer_arrows() {
+        let mut cube = CubieCube::default();
+        assert_eq!(last_layer(&cube, &ColorScheme::western()).matches("class=\"arrow\"").count(), 0);
+
+        // Ua perm cycles three edges
+        cube.apply_moves(&[R1, U3, R1, U1, R1, U1, R1, U3, R3, U3, R2]);
+        let svg = last_layer(&cube, &ColorScheme::western());
+        assert_eq!(svg.matches("class=\"arrow\"").count(), 3);
+        assert_eq!(svg.matches("marker-start").count(), 0);
+        assert_eq!(svg.matches("<rect").count(), 9 + 12);
+
+        // T perm swaps two edges and two corners
+        let mut cube = CubieCube::default();
+        cube.apply_moves(&[R1, U1, R3, U3, R3, F1, R2, U3, R3, U3, R1, U1, R3, F3]);
+        let svg = last_layer(&cube, &ColorScheme::western());
+        assert_eq!(svg.matches("class=\"arrow\"").count(), 2);
+        assert_eq!(svg.matches("marker-start").count(), 2);
+    }
+
+    #[test]
+    fn last_layer_skips_d_pieces() {
+        // R brings a D corner and a middle layer edge into the U layer, and moves URF to UBR
+        let mut cube = CubieCube::default();
+        cube.apply_moves(&[R1]);
+        let svg = last_layer(&cube, &ColorScheme::western());
+        assert_eq!(svg.matches("class=\"arrow\"").count(), 1);
+
+        // Every arrow stays inside the viewBox
+        let size = 3.0*STICKER + 2.0*STICKER/3.0 + 2.0*GAP;
+        for line in svg.lines().filter(|l| l.starts_with("<line")) {
+            for attr in ["x1", "y1", "x2", "y2"] {
+                let value = line.split(&format!(" {}=\"", attr)).nth(1).unwrap().split('"').next().unwrap();
+                let value: f64 = value.parse().unwrap();
+                assert!((0.0..=size).contains(&value), "{} in {}", attr, line);
+            }
+        }
+    }
+}
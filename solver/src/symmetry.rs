@@ -0,0 +1,222 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    common::{Corner, Edge, N_FLIP, N_SLICE, N_TWIST},
+    cubie::CubieCube,
+};
+
+// The 16 symmetries of the cube that keep the UD axis in place, generated by a half turn of
+// the whole cube about F, a quarter turn about U and the left/right reflection. Conjugating
+// by one of them doesn't change how far a cube is from phase 2, so the phase 1 pruning table
+// only needs one entry per class of equivalent flip and slice coordinates
+pub(crate) const N_SYM: usize = 16;
+/// Classes of flip and slice coordinates under the 16 symmetries
+pub(crate) const N_FLIPSLICE_CLASS: usize = 64430;
+
+type Co = Corner;
+type Ed = Edge;
+
+// Whole cube turned 180 degrees about the F face
+const S_F2: CubieCube = CubieCube {
+    cp: [Co::DFL, Co::DFR, Co::DBR, Co::DBL, Co::UFL, Co::UFR, Co::UBR, Co::UBL],
+    co: [0; 8],
+    ep: [Ed::DL, Ed::DF, Ed::DR, Ed::DB, Ed::UL, Ed::UF, Ed::UR, Ed::UB, Ed::FL, Ed::FR, Ed::BR, Ed::BL],
+    eo: [0; 12],
+};
+
+// Whole cube turned 90 degrees about the U face, the slice edges end up flipped
+const S_U4: CubieCube = CubieCube {
+    cp: [Co::UBR, Co::UFR, Co::UFL, Co::UBL, Co::DBR, Co::DFR, Co::DFL, Co::DBL],
+    co: [0; 8],
+    ep: [Ed::UB, Ed::UR, Ed::UF, Ed::UL, Ed::DB, Ed::DR, Ed::DF, Ed::DL, Ed::BR, Ed::FR, Ed::FL, Ed::BL],
+    eo: [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1],
+};
+
+// Mirrored left to right, every corner gets a mirrored orientation (see `corner_multiply`)
+const S_LR2: CubieCube = CubieCube {
+    cp: [Co::UFL, Co::UFR, Co::UBR, Co::UBL, Co::DFL, Co::DFR, Co::DBR, Co::DBL],
+    co: [3; 8],
+    ep: [Ed::UL, Ed::UF, Ed::UR, Ed::UB, Ed::DL, Ed::DF, Ed::DR, Ed::DB, Ed::FL, Ed::FR, Ed::BR, Ed::BL],
+    eo: [0; 12],
+};
+
+/// Symmetry `8*f2 + 2*u4 + lr2` is `S_F2^f2 * S_U4^u4 * S_LR2^lr2`, so 0 is the identity
+fn sym_cubes() -> Vec<CubieCube> {
+    let mut syms = Vec::with_capacity(N_SYM);
+    let mut cube = CubieCube::default();
+    for _ in 0..2 {
+        for _ in 0..4 {
+            for _ in 0..2 {
+                syms.push(cube.clone());
+                cube.multiply(&S_LR2);
+            }
+            cube.multiply(&S_U4);
+        }
+        cube.multiply(&S_F2);
+    }
+    syms
+}
+
+/// Lookups to reduce phase 1 coordinates by symmetry, about 3.5 MB. Generated rather than
+/// saved since it only takes a moment
+pub struct Symmetries {
+    // `S * twist * S^-1` for each twist and symmetry
+    twist_conj: Vec<u16>,
+    // Class of each `N_FLIP*slice + flip`, and the symmetry that takes its representative to it
+    flipslice_class: Vec<u16>,
+    flipslice_sym: Vec<u8>,
+    // Smallest `N_FLIP*slice + flip` in each class
+    flipslice_rep: Vec<u32>,
+    // Bit s set if symmetry s leaves the class representative where it is
+    flipslice_self: Vec<u16>,
+}
+
+impl Symmetries {
+    /// Bytes held by the lookups
+    pub const BYTES: usize = 2*N_TWIST*N_SYM + 3*N_FLIP*N_SLICE + 6*N_FLIPSLICE_CLASS;
+
+    pub fn new() -> Self {
+        let syms = sym_cubes();
+        let inverse: Vec<usize> = syms.iter()
+            .map(|s| syms.iter().position(|t| (s.clone() * t.clone()).is_solved()).expect("symmetries are a group"))
+            .collect();
+        // S * c * S^-1 and S^-1 * c * S
+        let conj = |s: usize, c: &CubieCube| syms[s].clone() * c.clone() * syms[inverse[s]].clone();
+        let conj_inv = |s: usize, c: &CubieCube| syms[inverse[s]].clone() * c.clone() * syms[s].clone();
+
+        let mut twist_conj = vec![0; N_TWIST*N_SYM];
+        let mut cube = CubieCube::default();
+        for twist in 0..N_TWIST {
+            cube.set_twist(twist as u16);
+            for s in 0..N_SYM {
+                twist_conj[N_SYM*twist + s] = conj(s, &cube).get_twist();
+            }
+        }
+
+        let mut flipslice_class = vec![u16::MAX; N_FLIP*N_SLICE];
+        let mut flipslice_sym = vec![0; N_FLIP*N_SLICE];
+        let mut flipslice_rep = Vec::with_capacity(N_FLIPSLICE_CLASS);
+        let mut flipslice_self = Vec::with_capacity(N_FLIPSLICE_CLASS);
+        let mut cube = CubieCube::default();
+        for slice in 0..N_SLICE {
+            cube.set_slice(slice as u16);
+            for flip in 0..N_FLIP {
+                let i = N_FLIP*slice + flip;
+                if flipslice_class[i] != u16::MAX {
+                    continue;
+                }
+                cube.set_flip(flip as u16);
+
+                let class = flipslice_rep.len() as u16;
+                let mut self_syms = 0;
+                for s in 0..N_SYM {
+                    let c = conj_inv(s, &cube);
+                    let j = N_FLIP*c.get_slice() as usize + c.get_flip() as usize;
+                    if j == i {
+                        self_syms |= 1 << s;
+                    }
+                    if flipslice_class[j] == u16::MAX {
+                        flipslice_class[j] = class;
+                        flipslice_sym[j] = s as u8;
+                    }
+                }
+                flipslice_rep.push(i as u32);
+                flipslice_self.push(self_syms);
+            }
+        }
+
+        Symmetries { twist_conj, flipslice_class, flipslice_sym, flipslice_rep, flipslice_self }
+    }
+
+    /// Index into the symmetry reduced phase 1 table, `N_TWIST*class + twist` with the twist
+    /// conjugated the same way as the flip and slice
+    pub(crate) fn index(&self, twist: usize, flip: usize, slice: usize) -> usize {
+        let i = N_FLIP*slice + flip;
+        let s = self.flipslice_sym[i] as usize;
+        N_TWIST*self.flipslice_class[i] as usize + self.twist_conj[N_SYM*twist + s] as usize
+    }
+
+    /// Flip and slice coordinates of a class representative
+    pub(crate) fn rep(&self, class: usize) -> (usize, usize) {
+        let i = self.flipslice_rep[class] as usize;
+        (i % N_FLIP, i / N_FLIP)
+    }
+
+    /// Other twists that give the same cube as `twist` with the class representative, when
+    /// the representative is symmetric
+    pub(crate) fn twins(&self, class: usize, twist: usize) -> impl Iterator<Item = usize> + '_ {
+        let self_syms = self.flipslice_self[class];
+        (1..N_SYM).filter(move |s| self_syms & 1 << s != 0).map(move |s| self.twist_conj[N_SYM*twist + s] as usize)
+    }
+}
+
+impl Default for Symmetries {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common::{Move, N_PERM_4}, strategies};
+    use strum::IntoEnumIterator;
+    use proptest::prelude::*;
+    use std::sync::OnceLock;
+
+    fn symmetries() -> &'static Symmetries {
+        static SYMMETRIES: OnceLock<Symmetries> = OnceLock::new();
+        SYMMETRIES.get_or_init(Symmetries::new)
+    }
+
+    #[test]
+    fn classes() {
+        let s = symmetries();
+        assert_eq!(s.flipslice_rep.len(), N_FLIPSLICE_CLASS);
+        assert!(s.flipslice_class.iter().all(|&c| (c as usize) < N_FLIPSLICE_CLASS));
+        assert_eq!(s.index(0, 0, 0), 0);
+        assert_eq!(s.rep(0), (0, 0));
+        assert!(s.flipslice_self.iter().all(|&m| m & 1 == 1));
+
+        let bytes = 2*s.twist_conj.len() + 2*s.flipslice_class.len() + s.flipslice_sym.len()
+            + 4*s.flipslice_rep.len() + 2*s.flipslice_self.len();
+        assert_eq!(bytes, Symmetries::BYTES);
+    }
+
+    #[test]
+    fn moves_stay_moves() {
+        // Conjugating a face turn by a symmetry gives another face turn, which is why distances
+        // are the same for every cube in a class
+        let moves: Vec<CubieCube> = Move::iter().map(|m| {
+            let mut c = CubieCube::default();
+            c.apply_move(m);
+            c
+        }).collect();
+        for s in sym_cubes() {
+            for m in &moves {
+                let c = s.clone() * m.clone() * s.inverse();
+                assert!(moves.contains(&c));
+            }
+        }
+    }
+
+    proptest! {
+        /// Cubes equivalent under symmetry have the same index, or a twin of it when the class
+        /// representative is symmetric
+        #[test]
+        fn prop_equivalent_cubes_share_index(cube in strategies::cube(), s in 0..N_SYM) {
+            let syms = sym_cubes();
+            let symmetries = symmetries();
+            let index = |c: &CubieCube| symmetries.index(c.get_twist() as usize, c.get_flip() as usize, c.get_slice() as usize);
+            let (i, j) = (index(&cube), index(&(syms[s].clone() * cube.clone() * syms[s].inverse())));
+            let (class, twist) = (i / N_TWIST, i % N_TWIST);
+            prop_assert_eq!(class, j / N_TWIST);
+            prop_assert!(twist == j % N_TWIST || symmetries.twins(class, twist).any(|t| t == j % N_TWIST));
+        }
+
+        #[test]
+        fn prop_slice_is_slice_sorted(cube in strategies::cube()) {
+            prop_assert_eq!(cube.get_slice() as usize, cube.get_slice_sorted() as usize / N_PERM_4);
+        }
+    }
+}
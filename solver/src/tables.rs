@@ -0,0 +1,114 @@
+use std::{fs, io, path::Path};
+
+use sha2::{Digest, Sha256};
+
+use crate::{error::Result, moves::table_path};
+
+/// SHA-256 of every table the full move set needs in each built in metric, as written on a
+/// little endian machine. A table that doesn't match was written by a different version or
+/// got damaged, delete it and it'll be generated again
+pub const DIGESTS: &[(&str, &str)] = &[
+    ("move_twist", "939129a843f648c462822075a0c27a7144ef566c8e7d1472c64d14e467a735a3"),
+    ("move_flip", "9039eab0160efe072d62be9e01ad28267d629321c780c0750d84cd34c382050c"),
+    ("move_slice_sorted", "e4d42bebabbb14c15355e33edda5495ab8daa9fe3ed0f25fe1acb71b4eecac7d"),
+    ("move_corners", "ec897513c00780af9e7d2f4b5c7ea4d5b05a917a39c6d03fd3882ca7f0c50298"),
+    ("move_ud_edges", "d398715d093f94316959cfcef9f13e9bf0ee03cfb00fd14b257f07d0134990f8"),
+    // Same table, both track where 4 edges are and what order they're in
+    ("move_u_edges", "dae8f3afd0571830c2b9b7a70b8aeead1b1481cc7f482cf6bb3052b137499183"),
+    ("move_d_edges", "dae8f3afd0571830c2b9b7a70b8aeead1b1481cc7f482cf6bb3052b137499183"),
+
+    ("prun_twist_slice", "13e3cde439c3726f6324bd6de55905f0efc57e945e0a9824af79de4755784390"),
+    ("prun_flip_slice", "070cd663d9dfdff418fe9614918a79025b94f993a555cf6a1bc8f9e628f5c7f7"),
+    ("prun_corners_slice", "b03df177f1e0ef3d6b1a80a601e5cc626a34909d0ed0f6fb4c16d74a56e48579"),
+    ("prun_ud_edges_slice", "21fb90ea3549fbab73b4b383c8faca8cd361dbbcd93257ea45724275e1c00ba6"),
+    // Only with `TableSet::Symmetric`
+    ("prun_phase1_sym", "c145ac24536e8300bef405ee7db56fc41113fa2b13a8d59f8f0353aa44251e69"),
+
+    ("prun_twist_slice_qtm", "b9f821b837ec95351cb78aaaea41ab522ebadae28eaf36f608d06925870baf68"),
+    ("prun_flip_slice_qtm", "81051639249917595c1738add69b61464e0b8ed137cdd6a56eb775ed8af11f85"),
+    ("prun_corners_slice_qtm", "a6aa6e40575d3e27fef3aa3750c6cdabd10cae0f376bc0d3aa15f0d3620f16f8"),
+    ("prun_ud_edges_slice_qtm", "c5358b11a043aaeb8850beaf201e18cca451b0712d2f5a0cb8a4425e8508a71e"),
+
+    ("prun_twist_slice_stm", "c787401ad45f39678edecfe76a4f3cc782dd416c888951a6710458b81af6631a"),
+    ("prun_flip_slice_stm", "072317677612479f6c1a3ed44c02b45e5217c7af3c420429f770f1d886441ad6"),
+    ("prun_corners_slice_stm", "0b67dd5e533b26c00f158140106b7c2f4b23d1fa759e06ce7435f63467e78b1d"),
+    ("prun_ud_edges_slice_stm", "519cd76daa170c5c80e41919884b09d3b61b91222c1cb126bc34ad553583e5dc"),
+];
+
+/// Hex SHA-256 of a table's bytes
+pub fn digest(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// What the digest says `name` should be, None for tables that aren't in `DIGESTS`
+pub fn expected(name: &str) -> Option<&'static str> {
+    DIGESTS.iter().find(|(n, _)| *n == name).map(|(_, d)| *d)
+}
+
+/// State of one table file, see `check`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableStatus {
+    Ok,
+    /// Not generated yet, the solver will make it when it needs it
+    Missing,
+    /// There but different, holds the digest of what's there
+    Mismatch(String),
+}
+
+/// Check every table in `DIGESTS` that's in `dir`, `dir` is relative to `tables/` like everywhere else
+pub fn check(dir: Option<&Path>) -> Result<Vec<(&'static str, TableStatus)>> {
+    let mut statuses = Vec::with_capacity(DIGESTS.len());
+    for &(name, expected) in DIGESTS {
+        let status = match fs::read(table_path(dir, name)) {
+            Ok(bytes) => {
+                let actual = digest(&bytes);
+                if actual == expected { TableStatus::Ok } else { TableStatus::Mismatch(actual) }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => TableStatus::Missing,
+            Err(e) => return Err(e.into()),
+        };
+        statuses.push((name, status));
+    }
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        metric::Metric,
+        moves::{load_d_edges_move_table, load_u_edges_move_table},
+        search::Solver,
+    };
+
+    #[test]
+    fn sha256() {
+        assert_eq!(digest(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
+    #[test]
+    fn check_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(check(Some(dir.path())).unwrap().iter().all(|(_, s)| *s == TableStatus::Missing));
+
+        Solver::new(Some(dir.path())).unwrap();
+        for (name, status) in check(Some(dir.path())).unwrap() {
+            // u/d edges are only needed for restricted move sets
+            let missing = name.ends_with("_qtm") || name.ends_with("_stm") || matches!(name, "move_u_edges" | "move_d_edges" | "prun_phase1_sym");
+            assert_eq!(status, if missing { TableStatus::Missing } else { TableStatus::Ok }, "{}", name);
+        }
+
+        Solver::with_metric(Some(dir.path()), Metric::Quarter).unwrap();
+        Solver::with_metric(Some(dir.path()), Metric::Slice).unwrap();
+        load_u_edges_move_table(Some(dir.path())).unwrap();
+        load_d_edges_move_table(Some(dir.path())).unwrap();
+        fs::write(dir.path().join("move_flip"), [1, 2, 3]).unwrap();
+        for (name, status) in check(Some(dir.path())).unwrap() {
+            match name {
+                "move_flip" => assert_eq!(status, TableStatus::Mismatch(digest(&[1, 2, 3]))),
+                "prun_phase1_sym" => assert_eq!(status, TableStatus::Missing),
+                _ => assert_eq!(status, TableStatus::Ok, "{}", name),
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+// Table directory shared by the tests in the other modules, so they don't read or write the
+// real tables/ and each table is only generated once per build directory
+
+use std::{env, path::{Path, PathBuf}, sync::OnceLock};
+
+use crate::search::Solver;
+
+/// target/tmp/tables, next to where cargo puts `CARGO_TARGET_TMPDIR` for integration tests.
+/// Kept between runs, `cargo clean` removes it
+pub(crate) fn dir() -> &'static Path {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        // The test binary is in target/<profile>/deps
+        let exe = env::current_exe().unwrap();
+        exe.ancestors().nth(3).unwrap().join("tmp/tables")
+    })
+}
+
+/// Solver for all the moves in the half turn metric
+pub(crate) fn solver() -> &'static Solver {
+    static SOLVER: OnceLock<Solver> = OnceLock::new();
+    SOLVER.get_or_init(|| Solver::new(Some(dir())).unwrap())
+}